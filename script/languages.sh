@@ -0,0 +1,42 @@
+#!/usr/bin/env bash
+
+# Regenerates src/issue/form/languages.txt from linguist's languages.yml at REVISION,
+# a release tag or commit of github-linguist/linguist, so that the list can be reproduced.
+#
+#   REVISION=<tag> script/languages.sh
+
+set -euo pipefail
+
+REVISION=${REVISION:?set REVISION to a linguist release tag or commit}
+URL="https://raw.githubusercontent.com/github-linguist/linguist/${REVISION}/lib/linguist/languages.yml"
+OUT="$(dirname "$0")/../src/issue/form/languages.txt"
+
+{
+  echo "# Languages Linguist knows, each followed by its aliases."
+  echo "# Generated by script/languages.sh from linguist's languages.yml at revision ${REVISION}."
+  echo "# cf. ${URL}"
+  curl -fsSL "${URL}" | awk '
+    function flush() {
+      if (name != "") print name ":" (aliases == "" ? "" : " " aliases)
+    }
+    /^[^ #-].*:[ \t]*$/ {
+      flush()
+      name = $0
+      sub(/:[ \t]*$/, "", name)
+      gsub(/^"|"$/, "", name)
+      aliases = ""
+      in_aliases = 0
+      next
+    }
+    /^  aliases:/ { in_aliases = 1; next }
+    /^  - / && in_aliases {
+      alias = substr($0, 5)
+      gsub(/^"|"$/, "", alias)
+      aliases = aliases (aliases == "" ? "" : ", ") alias
+      next
+    }
+    /^  [^ -]/ { in_aliases = 0 }
+    END { flush() }
+  '
+} > "${OUT}.tmp"
+mv "${OUT}.tmp" "${OUT}"
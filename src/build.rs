@@ -0,0 +1,101 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use crate::{
+    issue,
+    layout::{self, Mode},
+};
+
+/// Writes the chooser and the pages it links to, a preview of every form
+/// and the stylesheet under `out`.
+pub fn build(directory: &Path, out: &Path) -> Result<()> {
+    fs::create_dir_all(out.join("assets"))
+        .with_context(|| format!("Failed to create {}", out.display()))?;
+
+    write(
+        &out.join("index.html"),
+        &issue::chooser::render(directory, false, Mode::Static)?.into_string(),
+    )?;
+    write(
+        &out.join(Mode::Static.link("blank")),
+        &issue::chooser::blank_issue(Mode::Static).into_string(),
+    )?;
+    if let Some(page) = issue::chooser::security_policy(directory, Mode::Static)? {
+        write(
+            &out.join(Mode::Static.link("security")),
+            &page.into_string(),
+        )?;
+    }
+    for yaml in &issue::chooser_templates(directory)? {
+        let page = if issue::template::is_markdown(yaml) {
+            match issue::template::load(directory.join(yaml), false)? {
+                (Some(template), diagnostics) => template.to_html(&diagnostics, Mode::Static),
+                (None, diagnostics) => {
+                    warn!("Failed to deserialize {}", yaml);
+                    layout::failure_page(yaml, &diagnostics, Mode::Static)
+                }
+            }
+        } else {
+            match issue::form::load(directory.join(yaml), false)? {
+                (Some(form), diagnostics) => {
+                    form.to_html(&diagnostics, &issue::form::Prefill::default(), Mode::Static)
+                }
+                (None, diagnostics) => {
+                    warn!("Failed to deserialize {}", yaml);
+                    layout::failure_page(yaml, &diagnostics, Mode::Static)
+                }
+            }
+        };
+        write(&out.join(Mode::Static.link(yaml)), &page.into_string())?;
+    }
+    write(
+        &out.join("assets/extra.css"),
+        &layout::stylesheet("extra.scss")
+            .expect("Stylesheet is embedded at compile-time, so this should never fail."),
+    )?;
+
+    info!("Wrote previews to {}", out.display());
+    Ok(())
+}
+
+fn write(file: &Path, contents: &str) -> Result<()> {
+    fs::write(file, contents).with_context(|| format!("Failed to write {}", file.display()))
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn build_directory() {
+        let root =
+            std::env::temp_dir().join(format!("gh-form-build-directory-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let directory = root.join("ISSUE_TEMPLATE");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("bug.yml"),
+            "name: Bug\ndescription: Report a bug\nbody:\n  - type: input\n    id: version\n    attributes:\n      label: Version\n",
+        )
+        .unwrap();
+
+        let out = root.join("dist");
+        build(&directory, &out).unwrap();
+        let mut files = fs::read_dir(&out)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(
+            files,
+            vec!["assets", "blank.html", "bug.yml.html", "index.html"]
+        );
+
+        let index = fs::read_to_string(out.join("index.html")).unwrap();
+        assert!(index.contains(r#"href="bug.yml.html""#));
+        assert!(!index.contains("EventSource"));
+    }
+}
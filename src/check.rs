@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::{diagnostic::Diagnostic, issue};
+
+/// Deserializes and validates every form, Markdown template and configuration under `directory`.
+/// Unknown keys are reported as well if `strict` is set.
+pub fn check(directory: &Path, strict: bool) -> Result<Vec<Diagnostic>> {
+    let mut diagnostics = vec![];
+    for yaml in issue::list_templates(directory)? {
+        let file = directory.join(&yaml);
+        if issue::config::is_config(&yaml) {
+            diagnostics.extend(issue::config::load(&file, strict)?.1);
+        } else if issue::template::is_markdown(&yaml) {
+            diagnostics.extend(issue::template::load(&file, strict)?.1);
+        } else {
+            diagnostics.extend(issue::form::load(&file, strict)?.1);
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Deserializes and validates every discussion category form under `directory`.
+pub fn check_discussions(directory: &Path, strict: bool) -> Result<Vec<Diagnostic>> {
+    let mut diagnostics = vec![];
+    for yaml in issue::list_templates(directory)? {
+        if !issue::template::is_markdown(&yaml) {
+            diagnostics.extend(issue::form::discussion::load(directory.join(&yaml), strict)?.1);
+        }
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn check_directory() {
+        let directory =
+            std::env::temp_dir().join(format!("gh-form-check-directory-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("bug.yml"),
+            "name: Bug\ndescription: Report a bug\nbody:\n  - type: inptu\n    attributes:\n      label: Version\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("dropdown.yml"),
+            "name: Dropdown\ndescription: Pick one\nbody:\n  - type: input\n    attributes:\n      label: Version\n  - type: dropdown\n    attributes:\n      label: OS\n      multiple: maybe\n      options: [Linux]\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("feature.yml"),
+            "name: Feature\ndescription: Request a feature\nbody:\n  - type: input\n    id: idea\n    attributes:\n      label: Idea\n      placeholer: Tell us\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("markdown.yml"),
+            "name: Markdown\ndescription: No inputs\nbody:\n  - type: markdown\n    attributes:\n      value: Thanks!\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("config.yaml"),
+            "contact_links:\n  - name: Help\n    url: example.com\n    about: Ask here.\n",
+        )
+        .unwrap();
+
+        let rules = check(&directory, true)
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| {
+                let location = diagnostic.location.unwrap();
+                let file = Path::new(&location.file)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned();
+                (file, location.line, diagnostic.rule.name())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                ("bug.yml".to_string(), 4, "deserialize"),
+                ("config.yaml".to_string(), 3, "invalid-url"),
+                ("dropdown.yml".to_string(), 10, "deserialize"),
+                ("feature.yml".to_string(), 8, "unknown-key"),
+                ("markdown.yml".to_string(), 4, "markdown-only-body"),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_directory() {
+        let directory = Path::new("no/such/ISSUE_TEMPLATE");
+        let err = check(directory, true).unwrap_err();
+        assert_eq!(err.to_string(), "Failed to read no/such/ISSUE_TEMPLATE");
+    }
+}
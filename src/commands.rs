@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Debug, Parser)]
 #[clap(name = "gh-form", about, version)]
@@ -14,10 +14,84 @@ pub enum Action {
     /// Start a local server to preview issue form
     Preview {
         /// Path to the directory where issue forms are located
-        #[clap(short, long, default_value = ".github/ISSUE_TEMPLATE")]
+        #[clap(
+            short,
+            long,
+            default_value = ".github/ISSUE_TEMPLATE",
+            default_value_if("discussion", None, Some(".github/DISCUSSION_TEMPLATE"))
+        )]
         directory: PathBuf,
         #[clap(short, long, default_value = "8047")]
         /// Port to bind
         port: u16,
+        /// Report keys GitHub does not know of
+        #[clap(long)]
+        strict: bool,
+        /// Preview discussion category forms instead of issue forms
+        #[clap(long)]
+        discussion: bool,
+    },
+    /// Export previews of issue forms as a static site
+    Build {
+        /// Path to the directory where issue forms are located
+        #[clap(short, long, default_value = ".github/ISSUE_TEMPLATE")]
+        directory: PathBuf,
+        /// Path to the directory where previews are written
+        #[clap(short, long, default_value = "dist")]
+        out: PathBuf,
+    },
+    /// Parse an issue body created from an issue form into JSON
+    Parse {
+        /// Path to the issue form the issue was created from
+        #[clap(short, long)]
+        form: PathBuf,
+        /// Path to the markdown file containing the issue body
+        body: PathBuf,
     },
+    /// Build a link that opens a new issue with the form prefilled
+    Url {
+        /// Path to the issue form
+        form: PathBuf,
+        /// Repository where the issue is opened, in the form of owner/name
+        #[clap(short, long)]
+        repo: String,
+        /// Title of the issue
+        #[clap(short, long)]
+        title: Option<String>,
+        /// Value of an element, in the form of id=value
+        #[clap(short, long = "set", value_name = "ID=VALUE")]
+        set: Vec<String>,
+    },
+    /// Validate issue forms without starting a server
+    Check {
+        /// Path to the directory where issue forms are located
+        #[clap(
+            short,
+            long,
+            default_value = ".github/ISSUE_TEMPLATE",
+            default_value_if("discussion", None, Some(".github/DISCUSSION_TEMPLATE"))
+        )]
+        directory: PathBuf,
+        /// Output format of diagnostics
+        #[clap(short, long, value_enum, default_value = "human")]
+        format: Format,
+        /// Ignore keys GitHub does not know of
+        #[clap(long)]
+        no_strict: bool,
+        /// Validate discussion category forms instead of issue forms
+        #[clap(long)]
+        discussion: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Code frames for humans
+    Human,
+    /// JSON array of diagnostics
+    Json,
+    /// SARIF 2.1.0 log for code scanning
+    Sarif,
+    /// Workflow commands for GitHub Actions annotations
+    Github,
 }
@@ -0,0 +1,233 @@
+use std::fmt::{self, Display};
+
+use maud::{html, Markup, Render};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Problems GitHub reports for templates, identified by names that never change
+/// once published so that they can be referred to from CI configurations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Deserialize,
+    DuplicateKey,
+    UnsupportedSyntax,
+    UnknownKey,
+    ImplicitType,
+    MarkdownOnlyBody,
+    DuplicateId,
+    InvalidId,
+    DuplicateLabel,
+    EmptyOptions,
+    DuplicateOption,
+    ReservedOption,
+    DefaultOutOfBounds,
+    MissingFrontMatter,
+    EmptyFrontMatterField,
+    EmptyContactLink,
+    InvalidUrl,
+    DuplicateContactLink,
+    UnknownPrefill,
+    UnknownPrefillOption,
+    InvalidProject,
+    MisplacedValidations,
+    UnknownLanguage,
+}
+
+impl Rule {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Deserialize => "deserialize",
+            Self::DuplicateKey => "duplicate-key",
+            Self::UnsupportedSyntax => "unsupported-syntax",
+            Self::UnknownKey => "unknown-key",
+            Self::ImplicitType => "implicit-type",
+            Self::MarkdownOnlyBody => "markdown-only-body",
+            Self::DuplicateId => "duplicate-id",
+            Self::InvalidId => "invalid-id",
+            Self::DuplicateLabel => "duplicate-label",
+            Self::EmptyOptions => "empty-options",
+            Self::DuplicateOption => "duplicate-option",
+            Self::ReservedOption => "reserved-option",
+            Self::DefaultOutOfBounds => "default-out-of-bounds",
+            Self::MissingFrontMatter => "missing-front-matter",
+            Self::EmptyFrontMatterField => "empty-front-matter-field",
+            Self::EmptyContactLink => "empty-contact-link",
+            Self::InvalidUrl => "invalid-url",
+            Self::DuplicateContactLink => "duplicate-contact-link",
+            Self::UnknownPrefill => "unknown-prefill",
+            Self::UnknownPrefillOption => "unknown-prefill-option",
+            Self::InvalidProject => "invalid-project",
+            Self::MisplacedValidations => "misplaced-validations",
+            Self::UnknownLanguage => "unknown-language",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::UnsupportedSyntax
+            | Self::ImplicitType
+            | Self::MissingFrontMatter
+            | Self::UnknownPrefill
+            | Self::UnknownPrefillOption
+            | Self::UnknownLanguage => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: String,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number.
+    pub column: usize,
+    /// Number of characters underlined with carets.
+    pub width: usize,
+    /// Whole source line the location points into.
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: Rule,
+    /// Dotted path to the offending node, e.g. `body[2].attributes.options[0]`.
+    pub path: String,
+    pub message: String,
+    pub location: Option<Location>,
+    /// Another place involved in the problem, such as the first of duplicate keys.
+    pub related: Option<Related>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Related {
+    pub message: String,
+    pub location: Location,
+}
+
+impl Diagnostic {
+    pub fn new(rule: Rule, path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            path: path.into(),
+            message: message.into(),
+            location: None,
+            related: None,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.rule.severity()
+    }
+
+    fn header(&self) -> String {
+        if self.path.is_empty() {
+            format!(
+                "{}[{}]: {}",
+                self.severity(),
+                self.rule.name(),
+                self.message
+            )
+        } else {
+            format!(
+                "{}[{}]: {}: {}",
+                self.severity(),
+                self.rule.name(),
+                self.path,
+                self.message
+            )
+        }
+    }
+
+    /// Renders the offending source line as a code frame,
+    /// followed by a note with the related location if any.
+    pub fn code_frame(&self) -> Option<String> {
+        let mut frame = code_frame(self.location.as_ref()?);
+        if let Some(related) = &self.related {
+            frame.push_str(&format!(
+                "\nnote: {}\n{}",
+                related.message,
+                code_frame(&related.location)
+            ));
+        }
+        Some(frame)
+    }
+}
+
+/// Renders the source line with carets under the offending node, e.g.
+///
+/// ```text
+///  --> bug.yml:4:9
+///   |
+/// 4 |     id: os name
+///   |         ^^^^^^^
+/// ```
+fn code_frame(location: &Location) -> String {
+    let gutter = " ".repeat(location.line.to_string().len());
+    let indent = location
+        .snippet
+        .chars()
+        .take(location.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let remaining = (location.snippet.chars().count() + 1).saturating_sub(location.column);
+    let carets = "^".repeat(location.width.min(remaining).max(1));
+    format!(
+        "{gutter}--> {}:{}:{}\n{gutter} |\n{} | {}\n{gutter} | {indent}{carets}",
+        location.file, location.line, location.column, location.line, location.snippet
+    )
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.header())?;
+        if let Some(frame) = self.code_frame() {
+            write!(f, "\n{frame}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Render for Diagnostic {
+    fn render(&self) -> Markup {
+        html! {
+            li.(self.severity().to_string()) {
+                code {(self.rule.name())}
+                " "
+                @if !self.path.is_empty() {
+                    code {(self.path)}
+                    " "
+                }
+                (self.message)
+                @if let Some(frame) = self.code_frame() {
+                    pre."code-frame" {(frame)}
+                }
+            }
+        }
+    }
+}
+
+pub fn render_all(diagnostics: &[Diagnostic]) -> Markup {
+    html! {
+        @if !diagnostics.is_empty() {
+            ul.diagnostics {
+                @for diagnostic in diagnostics {
+                    (diagnostic)
+                }
+            }
+        }
+    }
+}
@@ -1,141 +1,246 @@
-use std::{
-    path::{Path, PathBuf},
-    sync::Arc,
-};
+use std::{convert::Infallible, path::PathBuf, sync::Arc};
 
-use anyhow::Result;
 use axum::{
     body::{boxed, Full},
     extract,
     http::{header, StatusCode, Uri},
-    response::{Html, IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Response,
+    },
     Extension,
 };
-use maud::{html, Render, DOCTYPE};
-use rsass::{
-    compile_scss,
-    output::{Format, Style},
-};
-use rust_embed::RustEmbed;
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 use tracing::{error, warn};
 
-use crate::issue;
+use crate::{
+    issue,
+    layout::{self, Mode},
+    pull_request,
+};
 
 pub struct AppState {
     pub directory: PathBuf,
+    /// Notified whenever a file in `directory` is created, modified or removed.
+    pub changes: broadcast::Sender<()>,
+    /// Whether previews report keys GitHub does not know of.
+    pub strict: bool,
+    /// Whether `directory` holds discussion category forms rather than issue templates.
+    pub discussion: bool,
 }
 
 pub async fn top_page(Extension(state): Extension<Arc<AppState>>) -> impl IntoResponse {
-    let yamls = list_yamls(&state.directory);
-    let config = if state.directory.join("config.yml").exists() {
-        Some(issue::config::deserialize(
-            &*state.directory.join("config.yml").to_string_lossy(),
-        ))
+    let chooser = if state.discussion {
+        issue::chooser::render_discussions(&state.directory, state.strict, Mode::Server)
     } else {
-        None
+        issue::chooser::render(&state.directory, state.strict, Mode::Server)
     };
-    yamls
-        .map(|value| {
-            html! {
-                (DOCTYPE)
-                html lang="en" {
-                    head {
-                        meta charset="UTF-8";
-                        title {"Issue Form Previewer"}
-                    }
-                    link
-                        rel="stylesheet"
-                        type="text/css"
-                        href="https://cdnjs.cloudflare.com/ajax/libs/github-markdown-css/5.1.0/github-markdown.min.css";
-                    link
-                        rel="stylesheet"
-                        type="text/css"
-                        href="/assets/extra.css";
-                    body ."markdown-body" {
-                        div."form-list-container" {
-                            @for yaml in value.iter().filter(|x| x != &"config.yml") {
-                                (issue::form::deserialize(&*state.directory.join(&yaml).to_string_lossy())
-                                    .map_or_else(
-                                        |err| {
-                                            warn!("Failed to deserialize {}", yaml);
-                                            html! {
-                                                div.summary {
-                                                    div {
-                                                        div {(format!("Failed to deserialize {yaml}"))}
-                                                        pre {(format!("{err}"))}
-                                                    }
-                                                }
-                                            }
-                                        },
-                                        |val| val.summarize(&yaml)
-                                    )
-                                )
-                            }
-                            @if let Some(ref c) = config {
-                                (match c {
-                                    Ok(val)=>{val.render()}
-                                    Err(err)=>{
-                                        warn!("Failed to deserialize config.yml");
-                                        html!{
-                                            div.summary {
-                                                div {
-                                                    div {(format!("Failed to deserialize config.yml"))}
-                                                    pre {(format!("{err}"))}
-                                                }
-                                            }
-                                        }
-                                    }
-                                })
-                            }
-                        }
-                        @if let Some(Ok(c)) = config {
-                            @if let Some(footnote) = c.footnote() {
-                                (footnote)
-                            }
-                        }
-                    }
-                }
-            }
-        })
-        .map_err(|err| {
+    chooser.map_err(|err| {
+        error!("{}", err);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+pub async fn blank_issue() -> Html<String> {
+    Html(issue::chooser::blank_issue(Mode::Server).into_string())
+}
+
+pub async fn security_policy(Extension(state): Extension<Arc<AppState>>) -> Response {
+    match issue::chooser::security_policy(&state.directory, Mode::Server) {
+        Ok(Some(page)) => Html(page.into_string()).into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => {
+            error!("{}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+pub async fn pull_requests(Extension(state): Extension<Arc<AppState>>) -> Html<String> {
+    Html(pull_request::index(&state.directory, Mode::Server).into_string())
+}
+
+pub async fn pull_request(
+    extract::Query(query): extract::Query<Vec<(String, String)>>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Response {
+    let name = query
+        .iter()
+        .rev()
+        .find(|(key, _)| key == "template")
+        .map(|(_, value)| value.as_str());
+    match pull_request::preview(&state.directory, name, Mode::Server) {
+        Ok(Some(page)) => Html(page.into_string()).into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => {
             error!("{}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
 }
 
 pub async fn preview(
     extract::Path(yaml): extract::Path<String>,
+    extract::Query(query): extract::Query<Vec<(String, String)>>,
     Extension(state): Extension<Arc<AppState>>,
-) -> impl IntoResponse {
-    issue::form::deserialize(&*state.directory.join(&yaml).to_string_lossy())
-        .map(|f| Html(f.to_html().into_string()))
-        .map_err(|err| match &*yaml {
-            "favicon.ico" => StatusCode::NOT_FOUND,
-            _ => {
-                error!("{}", err);
-                StatusCode::INTERNAL_SERVER_ERROR
-            }
-        })
+) -> Response {
+    if !is_listed(&state, &yaml) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    if state.discussion {
+        return preview_discussion(&yaml, &state);
+    }
+    if issue::template::is_markdown(&yaml) {
+        return preview_template(&yaml, &state);
+    }
+    match issue::form::load(state.directory.join(&yaml), state.strict) {
+        Ok((Some(form), mut diagnostics)) => {
+            let prefill = issue::form::Prefill::from_query(query);
+            diagnostics.extend(form.validate_prefill(&prefill));
+            Html(
+                form.to_html(&diagnostics, &prefill, Mode::Server)
+                    .into_string(),
+            )
+            .into_response()
+        }
+        Ok((None, diagnostics)) => {
+            warn!("Failed to deserialize {}", yaml);
+            let page = layout::failure_page(&yaml, &diagnostics, Mode::Server);
+            (StatusCode::UNPROCESSABLE_ENTITY, Html(page.into_string())).into_response()
+        }
+        Err(err) => {
+            error!("{}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
 }
 
-fn list_yamls<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
-    let mut yamls = std::fs::read_dir(path)?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            if entry.file_type().ok()?.is_file() {
-                let file_name = entry.file_name().to_string_lossy().into_owned();
-                if file_name.ends_with(".yaml") || file_name.ends_with(".yml") {
-                    Some(file_name)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<_>>();
-    yamls.sort();
-    Ok(yamls)
+fn preview_discussion(yaml: &str, state: &AppState) -> Response {
+    let category = issue::form::discussion::category(yaml);
+    match issue::form::discussion::load(state.directory.join(yaml), state.strict) {
+        Ok((Some(form), diagnostics)) => Html(
+            form.to_html(category, &diagnostics, Mode::Server)
+                .into_string(),
+        )
+        .into_response(),
+        Ok((None, diagnostics)) => {
+            warn!("Failed to deserialize {}", yaml);
+            let page = layout::failure_page(yaml, &diagnostics, Mode::Server);
+            (StatusCode::UNPROCESSABLE_ENTITY, Html(page.into_string())).into_response()
+        }
+        Err(err) => {
+            error!("{}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+fn preview_template(name: &str, state: &AppState) -> Response {
+    match issue::template::load(state.directory.join(name), state.strict) {
+        Ok((Some(template), diagnostics)) => {
+            Html(template.to_html(&diagnostics, Mode::Server).into_string()).into_response()
+        }
+        Ok((None, diagnostics)) => {
+            warn!("Failed to deserialize {}", name);
+            let page = layout::failure_page(name, &diagnostics, Mode::Server);
+            (StatusCode::UNPROCESSABLE_ENTITY, Html(page.into_string())).into_response()
+        }
+        Err(err) => {
+            error!("{}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+pub async fn link(
+    extract::Query(query): extract::Query<Vec<(String, String)>>,
+    Extension(state): Extension<Arc<AppState>>,
+) -> Response {
+    let param = |key: &str| {
+        query
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default()
+    };
+    let (template, repo) = (param("template"), param("repo"));
+    if !is_listed(&state, &template) || issue::template::is_markdown(&template) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let fields = query.into_iter().filter(|(key, _)| key != "repo");
+    // Empty fields left in the page's own form are not meant to be prefilled.
+    let prefill =
+        issue::form::Prefill::from_query(fields.filter(|(_, value)| !value.is_empty()).collect());
+    match issue::form::load(state.directory.join(&template), state.strict) {
+        Ok((Some(form), mut diagnostics)) => {
+            diagnostics.extend(form.validate_prefill(&prefill));
+            let page = form.to_link_html(&template, &repo, &prefill, &diagnostics, Mode::Server);
+            Html(page.into_string()).into_response()
+        }
+        Ok((None, diagnostics)) => {
+            warn!("Failed to deserialize {}", template);
+            let page = layout::failure_page(&template, &diagnostics, Mode::Server);
+            (StatusCode::UNPROCESSABLE_ENTITY, Html(page.into_string())).into_response()
+        }
+        Err(err) => {
+            warn!("{}", err);
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
+
+pub async fn submit(
+    extract::Path(yaml): extract::Path<String>,
+    Extension(state): Extension<Arc<AppState>>,
+    extract::Form(fields): extract::Form<Vec<(String, String)>>,
+) -> Response {
+    if !is_listed(&state, &yaml) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    match issue::form::load(state.directory.join(&yaml), false) {
+        Ok((Some(form), _)) => {
+            let answers = issue::form::answers(fields);
+            Html(
+                form.to_submission_html(&answers, Mode::Server)
+                    .into_string(),
+            )
+            .into_response()
+        }
+        Ok((None, diagnostics)) => {
+            warn!("Failed to deserialize {}", yaml);
+            let page = layout::failure_page(&yaml, &diagnostics, Mode::Server);
+            (StatusCode::UNPROCESSABLE_ENTITY, Html(page.into_string())).into_response()
+        }
+        Err(err) => {
+            error!("{}", err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Whether `name`, which comes from the request, is a template in the directory.
+/// Only such names are opened, so that requests cannot read files elsewhere.
+fn is_listed(state: &AppState, name: &str) -> bool {
+    let listed = issue::list_templates(&state.directory)
+        .is_ok_and(|templates| templates.iter().any(|template| template == name));
+    if !listed {
+        warn!(
+            "No template named {} in {}",
+            name,
+            state.directory.display()
+        );
+    }
+    listed
+}
+
+pub async fn events(
+    Extension(state): Extension<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(state.changes.subscribe())
+        .filter_map(|change| change.ok().map(|_| Ok(Event::default().data("reload"))));
+    Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
 pub async fn serve_static(uri: Uri) -> impl IntoResponse {
@@ -152,10 +257,6 @@ pub async fn serve_static(uri: Uri) -> impl IntoResponse {
     StaticFile(path)
 }
 
-#[derive(RustEmbed)]
-#[folder = "src/assets/"]
-struct Asset;
-
 struct StaticFile<T>(pub T);
 
 impl<T> IntoResponse for StaticFile<T>
@@ -165,25 +266,11 @@ where
     fn into_response(self) -> Response {
         let path = self.0.into();
 
-        match Asset::get(path.as_str()) {
-            Some(content) => {
-                let compiled_css = String::from_utf8(
-                    compile_scss(
-                        &content.data,
-                        Format {
-                            style: Style::Compressed,
-                            precision: 5,
-                        },
-                    )
-                    .expect("Stylesheet is embedded at compile-time, so this should never fail.")
-                    .to_vec(),
-                )
-                .expect("Stylesheet is embedded at compile-time, so this should never fail.");
-                Response::builder()
-                    .header(header::CONTENT_TYPE, "text/css")
-                    .body(boxed(Full::from(compiled_css)))
-                    .unwrap()
-            }
+        match layout::stylesheet(&path) {
+            Some(compiled_css) => Response::builder()
+                .header(header::CONTENT_TYPE, "text/css")
+                .body(boxed(Full::from(compiled_css)))
+                .unwrap(),
             None => Response::builder()
                 .status(StatusCode::NOT_FOUND)
                 .body(boxed(Full::from(format!("Asset {path} not found"))))
@@ -1,2 +1,78 @@
+pub mod chooser;
 pub mod config;
 pub mod form;
+pub mod template;
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// Lists the YAML files and Markdown templates in `path` in the order GitHub shows templates,
+/// i.e. by file name regardless of case.
+pub fn list_templates<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+    let path = path.as_ref();
+    let mut yamls = std::fs::read_dir(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            if entry.file_type().ok()?.is_file() {
+                let file_name = entry.file_name().to_string_lossy().into_owned();
+                if file_name.ends_with(".yaml")
+                    || file_name.ends_with(".yml")
+                    || template::is_markdown(&file_name)
+                {
+                    Some(file_name)
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    yamls.sort_by_key(|yaml| (yaml.to_lowercase(), yaml.clone()));
+    Ok(yamls)
+}
+
+/// Lists the templates GitHub offers in the chooser, in its order.
+/// Markdown files without front matter, such as a README, are not templates to GitHub;
+/// only `check` reports them.
+pub fn chooser_templates(directory: &Path) -> Result<Vec<String>> {
+    Ok(list_templates(directory)?
+        .into_iter()
+        .filter(|name| !config::is_config(name))
+        .filter(|name| {
+            !template::is_markdown(name) || template::has_front_matter(directory.join(name))
+        })
+        .collect())
+}
+
+/// Root of the repository whose templates are in `directory`, i.e. the parent of `.github`.
+pub fn repository_root(directory: &Path) -> PathBuf {
+    match directory.parent().and_then(Path::parent) {
+        Some(root) if !root.as_os_str().is_empty() => root.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Directories GitHub looks for community health files in, namely `.github`, the root and `docs`.
+pub fn community_directories(directory: &Path) -> Vec<PathBuf> {
+    let root = repository_root(directory);
+    vec![root.join(".github"), root.clone(), root.join("docs")]
+}
+
+/// Finds the security policy of the repository whose templates are in `directory`.
+pub fn find_security_policy(directory: &Path) -> Option<PathBuf> {
+    community_directories(directory)
+        .iter()
+        .filter_map(|directory| std::fs::read_dir(directory).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case("SECURITY.md"))
+        })
+}
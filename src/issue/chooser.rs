@@ -0,0 +1,245 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use maud::{html, Markup, Render};
+use tracing::warn;
+
+use crate::{
+    diagnostic, issue,
+    layout::{self, Mode},
+    pull_request,
+};
+
+/// Renders the template chooser for the templates under `directory`,
+/// listing templates, contact links and the security policy in the order GitHub does.
+/// Keys the templates and the configuration do not know of are reported as well if `strict` is set.
+pub fn render(directory: &Path, strict: bool, mode: Mode) -> Result<Markup> {
+    let templates = issue::chooser_templates(directory)?;
+    let config = issue::config::find(directory).map(|file| {
+        let name = file
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        (name, issue::config::load(file, strict))
+    });
+    // Blank issues are allowed unless the configuration says otherwise.
+    let blank_issues_enabled = match &config {
+        None => true,
+        Some((_, Ok((Some(config), _)))) => config.blank_issues_enabled(),
+        Some(_) => false,
+    };
+    Ok(layout::page(
+        mode,
+        html! {
+            div."form-list-container" {
+                @for template in &templates {
+                    (summary(directory, template, strict, mode))
+                }
+                @if let Some((name, c)) = &config {
+                    (match c {
+                        Ok((Some(val), diagnostics)) => html! {
+                            (val.render())
+                            (diagnostic::render_all(diagnostics))
+                        },
+                        Ok((None, diagnostics)) => {
+                            warn!("Failed to deserialize {}", name);
+                            failure(name, diagnostic::render_all(diagnostics))
+                        }
+                        Err(err) => {
+                            warn!("Failed to deserialize {}", name);
+                            failure(name, html! { pre {(format!("{err}"))} })
+                        }
+                    })
+                }
+                @if issue::find_security_policy(directory).is_some() {
+                    div.summary {
+                        div {
+                            strong.name {"Report a security vulnerability"}
+                            div.description {"Please review our security policy for more details"}
+                        }
+                        a.button href=(mode.link("security")) {"View policy"}
+                    }
+                }
+            }
+            @if blank_issues_enabled {
+                div.footnote {
+                    "Don't see your issue here? "
+                    a href=(mode.link("blank")) {"Open a blank issue."}
+                }
+            }
+            // Pull request templates are only served by the preview server, which picks them by query.
+            @if mode == Mode::Server && !pull_request::find(directory).is_empty() {
+                div.footnote {
+                    a href="pull-requests" {"Preview pull request templates."}
+                }
+            }
+        },
+    ))
+}
+
+/// Renders the list of discussion category forms under `directory` with their categories.
+/// Keys the forms do not know of are reported as well if `strict` is set.
+pub fn render_discussions(directory: &Path, strict: bool, mode: Mode) -> Result<Markup> {
+    let yamls = issue::list_templates(directory)?
+        .into_iter()
+        .filter(|name| !issue::template::is_markdown(name))
+        .collect::<Vec<_>>();
+    Ok(layout::page(
+        mode,
+        html! {
+            div."form-list-container" {
+                @for yaml in &yamls {
+                    @let category = issue::form::discussion::category(yaml);
+                    (match issue::form::discussion::load(directory.join(yaml), strict) {
+                        Ok((Some(form), diagnostics)) => html! {
+                            (form.summarize(category, &mode.link(yaml)))
+                            (diagnostic::render_all(&diagnostics))
+                        },
+                        Ok((None, diagnostics)) => {
+                            warn!("Failed to deserialize {}", yaml);
+                            failure(yaml, diagnostic::render_all(&diagnostics))
+                        }
+                        Err(err) => {
+                            warn!("Failed to deserialize {}", yaml);
+                            failure(yaml, html! { pre {(format!("{err}"))} })
+                        }
+                    })
+                }
+            }
+        },
+    ))
+}
+
+/// Renders the page GitHub shows for a blank issue, which has no template at all.
+pub fn blank_issue(mode: Mode) -> Markup {
+    layout::page(
+        mode,
+        html! {
+            div."issue-title" {
+                label {
+                    h3 required="required" {"Title"}
+                }
+                input."form-input" type="text" name="issue-title" placeholder="Title";
+            }
+            label {
+                h3 {"Add a description"}
+            }
+            textarea."form-textarea" name="issue-body" placeholder="Add your description here..." {}
+        },
+    )
+}
+
+/// Renders the security policy linked from the chooser, if the repository has one.
+pub fn security_policy(directory: &Path, mode: Mode) -> Result<Option<Markup>> {
+    let file = match issue::find_security_policy(directory) {
+        Some(file) => file,
+        None => return Ok(None),
+    };
+    let policy =
+        fs::read_to_string(&file).with_context(|| format!("Failed to open {}", file.display()))?;
+    Ok(Some(layout::page(
+        mode,
+        html! {
+            div."issue-preview" {(issue::form::Markdown(policy))}
+        },
+    )))
+}
+
+/// Summarizes the form or Markdown template `name` for the chooser.
+fn summary(directory: &Path, name: &str, strict: bool, mode: Mode) -> Markup {
+    let file = directory.join(name);
+    let loaded = if issue::template::is_markdown(name) {
+        issue::template::load(file, strict).map(|(template, diagnostics)| {
+            (
+                template.map(|template| template.summarize(&mode.link(name))),
+                diagnostics,
+            )
+        })
+    } else {
+        issue::form::load(file, strict).map(|(form, diagnostics)| {
+            (
+                form.map(|form| form.summarize(&mode.link(name))),
+                diagnostics,
+            )
+        })
+    };
+    match loaded {
+        Ok((Some(summary), diagnostics)) => html! {
+            (summary)
+            (diagnostic::render_all(&diagnostics))
+        },
+        Ok((None, diagnostics)) => {
+            warn!("Failed to deserialize {}", name);
+            failure(name, diagnostic::render_all(&diagnostics))
+        }
+        Err(err) => {
+            warn!("Failed to deserialize {}", name);
+            failure(name, html! { pre {(format!("{err}"))} })
+        }
+    }
+}
+
+fn failure(yaml: &str, details: Markup) -> Markup {
+    html! {
+        div.summary {
+            div {
+                div {(format!("Failed to deserialize {yaml}"))}
+            }
+        }
+        (details)
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+
+    #[test]
+    fn chooser() {
+        let root = std::env::temp_dir().join(format!("gh-form-chooser-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let directory = root.join(".github/ISSUE_TEMPLATE");
+        fs::create_dir_all(&directory).unwrap();
+        for (yaml, name) in [("b.yml", "Second"), ("A.yml", "First"), ("d.yml", "Fourth")] {
+            fs::write(
+                directory.join(yaml),
+                format!("name: {name}\ndescription: d\nlabels: [bug, triage]\nbody:\n  - type: input\n    attributes:\n      label: Version\n"),
+            )
+            .unwrap();
+        }
+        fs::write(directory.join("c.md"), "---\nname: Third\nabout: d\n---\n").unwrap();
+        fs::write(directory.join("README.md"), "# Templates\n").unwrap();
+        fs::write(root.join("SECURITY.md"), "# Security\n").unwrap();
+
+        let html = render(&directory, false, Mode::Static)
+            .unwrap()
+            .into_string();
+        assert!(!html.contains("README.md"));
+        let position = |text| html.find(text).unwrap();
+        assert!(position("First") < position("Second"));
+        assert!(position("Second") < position("Third"));
+        assert!(position("Third") < position("Fourth"));
+        assert!(html.contains(r#"href="c.md.html""#));
+        assert!(html.contains(
+            r#"<div class="labels"><span class="label-chip">bug</span><span class="label-chip">triage</span></div>"#
+        ));
+        assert!(html.contains(r#"<a class="button" href="security.html">View policy</a>"#));
+        assert!(html.contains(r#"<a href="blank.html">Open a blank issue.</a>"#));
+
+        fs::write(
+            directory.join("config.yml"),
+            "blank_issues_enabled: false\ncontact_link: []\n",
+        )
+        .unwrap();
+        let html = render(&directory, false, Mode::Static)
+            .unwrap()
+            .into_string();
+        assert!(!html.contains("Open a blank issue."));
+        assert!(!html.contains("unknown key `contact_link`"));
+        let html = render(&directory, true, Mode::Static)
+            .unwrap()
+            .into_string();
+        assert!(html.contains("unknown key `contact_link`, did you mean `contact_links`?"));
+    }
+}
@@ -1,21 +1,68 @@
-use std::{fmt::Display, fs, path::Path};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use maud::{html, Markup, Render};
 use serde::Deserialize;
 
-pub fn deserialize(file: impl AsRef<Path> + Display + Copy) -> Result<Config> {
-    let f = fs::File::open(file).with_context(|| format!("Failed to open {}", file))?;
-    let config: Config = serde_yaml::from_reader(f)?;
-    return Ok(config);
+use crate::{
+    diagnostic::{Diagnostic, Rule},
+    yaml::{Document, Schema},
+};
+
+/// Names GitHub looks for the template chooser configuration under, in order of precedence.
+const FILE_NAMES: [&str; 2] = ["config.yml", "config.yaml"];
+
+/// Whether `yaml` in a template directory is the chooser configuration rather than a form.
+pub fn is_config(yaml: &str) -> bool {
+    FILE_NAMES.contains(&yaml)
+}
+
+/// Finds the chooser configuration in `directory`, if any.
+pub fn find(directory: &Path) -> Option<PathBuf> {
+    FILE_NAMES
+        .iter()
+        .map(|name| directory.join(name))
+        .find(|file| file.is_file())
+}
+
+/// Reads and validates the template chooser configuration at `file`.
+/// Keys the configuration does not know of are reported as well if `strict` is set.
+pub fn load(file: impl AsRef<Path>, strict: bool) -> Result<(Option<Config>, Vec<Diagnostic>)> {
+    let document = Document::open(file)?;
+    let (config, mut diagnostics) = document.load(Config::validate);
+    diagnostics.splice(0..0, document.lint(&SCHEMA, strict));
+    Ok((config, diagnostics))
 }
 
+/// Keys `Config` deserializes and which of them take strings.
+const SCHEMA: Schema = Schema::Mapping(&[
+    ("blank_issues_enabled", Schema::Any),
+    (
+        "contact_links",
+        Schema::Sequence(&Schema::Mapping(&[
+            ("name", Schema::Text),
+            ("url", Schema::Text),
+            ("about", Schema::Text),
+        ])),
+    ),
+]);
+
+// cf. https://docs.github.com/en/communities/using-templates-to-encourage-useful-issues-and-pull-requests/configuring-issue-templates-for-your-repository#configuring-the-template-chooser
 #[derive(Debug, Deserialize)]
 pub struct Config {
+    #[serde(default = "default_true")]
     blank_issues_enabled: bool,
+    #[serde(default)]
     contact_links: Vec<ContactLink>,
 }
 
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Debug, Deserialize)]
 struct ContactLink {
     name: String,
@@ -44,15 +91,60 @@ impl Render for Config {
 }
 
 impl Config {
-    pub fn footnote(&self) -> Option<Markup> {
-        if self.blank_issues_enabled {
-            Some(html! { div.footnote {"Don't see your issue here? Open a blank issue."} })
-        } else {
-            None
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let mut names = HashMap::new();
+        for (index, link) in self.contact_links.iter().enumerate() {
+            for (key, value) in [("name", &link.name), ("about", &link.about)] {
+                if value.trim().is_empty() {
+                    diagnostics.push(Diagnostic::new(
+                        Rule::EmptyContactLink,
+                        format!("contact_links[{index}].{key}"),
+                        format!("{key} must not be empty"),
+                    ));
+                }
+            }
+            if !is_http_url(&link.url) {
+                diagnostics.push(Diagnostic::new(
+                    Rule::InvalidUrl,
+                    format!("contact_links[{index}].url"),
+                    format!("url `{}` must be an absolute http or https URL", link.url),
+                ));
+            }
+            if let Some(first) = names.get(link.name.as_str()) {
+                diagnostics.push(Diagnostic::new(
+                    Rule::DuplicateContactLink,
+                    format!("contact_links[{index}].name"),
+                    format!(
+                        "name `{}` is already used by contact_links[{first}]",
+                        link.name
+                    ),
+                ));
+            } else {
+                names.insert(link.name.as_str(), index);
+            }
         }
+        diagnostics
+    }
+
+    pub fn blank_issues_enabled(&self) -> bool {
+        self.blank_issues_enabled
     }
 }
 
+fn is_http_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    let rest = match lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+    {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    !host.is_empty() && !url.contains(char::is_whitespace)
+}
+
 #[cfg(test)]
 mod unit_test {
     use super::*;
@@ -80,4 +172,37 @@ mod unit_test {
             r#"<div class="summary"><div><strong class="name">GitHub Community Support</strong><div class="description">Please ask and answer questions here.</div></div><a class="button external" href="https://github.com/orgs/community/discussions" target="_blank" rel="noopener noreferrer">Open</a></div><div class="summary"><div><strong class="name">GitHub Security Bug Bounty</strong><div class="description">Please report security vulnerabilities here.</div></div><a class="button external" href="https://bounty.github.com/" target="_blank" rel="noopener noreferrer">Open</a></div>"#
         )
     }
+
+    #[test]
+    fn validate() {
+        let config: Config = serde_yaml::from_str(
+            r#"
+contact_links:
+  - name: Support
+    url: https://example.com/support
+    about: Ask here.
+  - name: Support
+    url: example.com
+    about: " "
+"#,
+        )
+        .unwrap();
+        assert!(config.blank_issues_enabled);
+        let rules = config
+            .validate()
+            .into_iter()
+            .map(|diagnostic| (diagnostic.rule.name(), diagnostic.path))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                ("empty-contact-link", "contact_links[1].about".to_string()),
+                ("invalid-url", "contact_links[1].url".to_string()),
+                (
+                    "duplicate-contact-link",
+                    "contact_links[1].name".to_string()
+                ),
+            ]
+        );
+    }
 }
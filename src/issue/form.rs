@@ -1,87 +1,89 @@
-use anyhow::{Context, Result};
-use maud::{html, Markup, PreEscaped, Render, DOCTYPE};
-use pulldown_cmark::{html, Event, Parser, Tag};
-use serde::Deserialize;
-use std::{
-    fmt::{Debug, Display},
-    fs,
-    path::Path,
+pub mod discussion;
+mod gfm;
+mod language;
+mod link;
+mod prefill;
+mod schema;
+mod submission;
+mod validate;
+
+pub(crate) use link::QUERY;
+pub use prefill::Prefill;
+pub use submission::answers;
+
+use anyhow::Result;
+use maud::{html, Markup, PreEscaped, Render};
+use pulldown_cmark::{html, Event, Tag};
+use serde::{de::IgnoredAny, Deserialize};
+use std::{collections::HashSet, fmt::Debug, path::Path};
+
+use crate::{
+    diagnostic::{self, Diagnostic},
+    layout::{self, Mode},
+    yaml::Document,
 };
 
-pub fn deserialize(file: impl AsRef<Path> + Display + Copy) -> Result<Form> {
-    let f = fs::File::open(file).with_context(|| format!("Failed to open {}", file))?;
-    let form: Form = serde_yaml::from_reader(f)?;
-    return Ok(form);
+/// Reads and validates the issue form at `file`.
+/// The form is `None` if it could not be deserialized, in which case the diagnostics say why.
+/// Keys the form does not know of are reported as well if `strict` is set.
+pub fn load(file: impl AsRef<Path>, strict: bool) -> Result<(Option<Form>, Vec<Diagnostic>)> {
+    let document = Document::open(file)?;
+    let (form, mut diagnostics) = document.load(Form::validate);
+    diagnostics.splice(0..0, document.lint(&schema::FORM, strict));
+    Ok((form, diagnostics))
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Form {
     name: String,
     description: String,
-    // The optional title is in the issue form's spec, but is not used
-    // for preview at the moment. Hence the warning suppression.
     // cf. https://docs.github.com/en/communities/using-templates-to-encourage-useful-issues-and-pull-requests/syntax-for-issue-forms#top-level-syntax
-    #[allow(dead_code)]
     title: Option<String>,
     #[serde(default = "default_empty_sequecelike")]
     labels: SequenceLike,
     #[serde(default = "default_empty_sequecelike")]
     assignees: SequenceLike,
+    /// Issue type the new issue gets, if the organization defines it.
+    #[serde(rename = "type")]
+    issue_type: Option<String>,
+    /// Projects the new issue is added to, each as `owner/number`.
+    #[serde(default = "default_empty_sequecelike")]
+    projects: SequenceLike,
     body: Vec<BodyType>,
 }
 
 impl Form {
-    pub fn to_html(&self) -> Markup {
-        html! {
-            (DOCTYPE)
-            html lang="en" {
-                head {
-                    meta charset="UTF-8";
-                    title {"Issue Form Previewer"}
-                }
-                link
-                    rel="stylesheet"
-                    type="text/css"
-                    href="https://cdnjs.cloudflare.com/ajax/libs/github-markdown-css/5.1.0/github-markdown.min.css";
-                link
-                    rel="stylesheet"
-                    type="text/css"
-                    href="/assets/extra.css";
-                body ."markdown-body" {
-                    article {
-                        table role="table" {
-                            thead {
-                                tr {
-                                    th align="left" {"Name"}
-                                    th align="left" {"About"}
-                                    th align="left" {"Labels"}
-                                    th align="left" {"Assignees"}
-                                }
-                            }
-                            tbody {
-                                tr {
-                                    td align="left" {(self.name)}
-                                    td align="left" {(self.description)}
-                                    td align="left" {(self.labels.join())}
-                                    td align="left" {(self.assignees.join())}
-                                }
-                            }
+    pub fn to_html(&self, diagnostics: &[Diagnostic], prefill: &Prefill, mode: Mode) -> Markup {
+        layout::page(
+            mode,
+            html! {
+                (diagnostic::render_all(diagnostics))
+                (metadata(
+                    &self.name,
+                    &self.description,
+                    &self.labels,
+                    &self.assignees,
+                    &[
+                        ("Type", self.issue_type.clone().unwrap_or_default()),
+                        ("Projects", self.projects.join()),
+                    ],
+                ))
+                (self.render_prefill(prefill))
+                form method="post" {
+                    div."issue-title" {
+                        label {
+                            h3 required="required" {"Title"}
                         }
+                        input."form-input" type="text" name="issue-title" placeholder="Title" value=[prefill.title.as_deref().or(self.title.as_deref())];
                     }
-                    table {
-                        tbody {
-                            tr {
-                                td {
-                                    @for item in &self.body {
-                                        (item.render())
-                                    }
-                                }
-                            }
-                        }
+                    (render_body(&self.body, prefill))
+                    // Submission needs the server to turn answers into an issue body.
+                    @if mode == Mode::Server {
+                        button."submit-button" type="submit" {"Submit new issue"}
                     }
                 }
-            }
-        }
+            },
+        )
     }
     pub fn summarize(&self, link: &str) -> Markup {
         html! {
@@ -89,6 +91,10 @@ impl Form {
                 div {
                     strong.name {(self.name)}
                     div.description {(self.description)}
+                    (label_chips(&self.labels.items()))
+                    @if let Some(title) = &self.title {
+                        div.title {"Title: " code {(title)}}
+                    }
                 }
                 a.button href=(link) {"Preview"}
             }
@@ -96,15 +102,104 @@ impl Form {
     }
 }
 
+/// Renders the elements of a form, which issue and discussion forms share.
+fn render_body(body: &[BodyType], prefill: &Prefill) -> Markup {
+    html! {
+        table {
+            tbody {
+                tr {
+                    td {
+                        @for (item, anchor) in body.iter().zip(anchors(body)) {
+                            (item.to_html(
+                                anchor.as_deref().unwrap_or_default(),
+                                item.id().and_then(|id| prefill.field(id)),
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Renders the table of what a template sets on new issues,
+/// followed by `extra` columns for keys only some kinds of templates have.
+pub fn metadata(
+    name: &str,
+    about: &str,
+    labels: &SequenceLike,
+    assignees: &SequenceLike,
+    extra: &[(&str, String)],
+) -> Markup {
+    html! {
+        article {
+            table role="table" {
+                thead {
+                    tr {
+                        th align="left" {"Name"}
+                        th align="left" {"About"}
+                        th align="left" {"Labels"}
+                        th align="left" {"Assignees"}
+                        @for (heading, _) in extra {
+                            th align="left" {(heading)}
+                        }
+                    }
+                }
+                tbody {
+                    tr {
+                        td align="left" {(name)}
+                        td align="left" {(about)}
+                        td align="left" {(labels.join())}
+                        td align="left" {(assignees.join())}
+                        @for (_, value) in extra {
+                            td align="left" {(value)}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Renders labels the way GitHub shows them next to templates in the chooser.
+pub fn label_chips(labels: &[String]) -> Markup {
+    html! {
+        @if !labels.is_empty() {
+            div.labels {
+                @for label in labels {
+                    span."label-chip" {(label)}
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
-enum SequenceLike {
+pub enum SequenceLike {
     Sequence(Vec<String>),
     CommaDelimited(String),
 }
 
+impl Default for SequenceLike {
+    fn default() -> Self {
+        default_empty_sequecelike()
+    }
+}
+
 impl SequenceLike {
-    fn join(&self) -> String {
+    pub fn items(&self) -> Vec<String> {
+        match self {
+            Self::Sequence(seq) => seq.clone(),
+            Self::CommaDelimited(labels) => labels
+                .split(',')
+                .map(|label| label.trim().to_string())
+                .filter(|label| !label.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn join(&self) -> String {
         match self {
             Self::Sequence(seq) => seq.join(", "),
             Self::CommaDelimited(labels) => labels.split(",").collect::<Vec<_>>().join(", "),
@@ -116,44 +211,114 @@ impl SequenceLike {
 #[serde(rename_all = "lowercase", tag = "type")]
 enum BodyType {
     Checkboxes {
-        id: String,
+        id: Option<String>,
         attributes: CheckboxesAttribute,
+        /// Checkboxes are required per option instead, so this is only kept to be reported.
+        validations: Option<IgnoredAny>,
     },
     Dropdown {
-        id: String,
+        id: Option<String>,
         attributes: DropdownAttribute,
         validations: Option<Validations>,
     },
     Input {
-        id: String,
+        id: Option<String>,
         attributes: InputAttribute,
         validations: Option<Validations>,
     },
     Markdown {
         attributes: MarkdownAttribute,
+        /// Markdown takes no answer to validate, so this is only kept to be reported.
+        validations: Option<IgnoredAny>,
     },
     Textarea {
-        id: String,
+        id: Option<String>,
         attributes: TextareaAttribute,
         validations: Option<Validations>,
     },
 }
 
+impl BodyType {
+    /// The id given in the form, which is optional except for markdown that never has one.
+    fn id(&self) -> Option<&str> {
+        match self {
+            Self::Checkboxes { id, .. }
+            | Self::Dropdown { id, .. }
+            | Self::Input { id, .. }
+            | Self::Textarea { id, .. } => id.as_deref(),
+            Self::Markdown { .. } => None,
+        }
+    }
+
+    fn label(&self) -> Option<&str> {
+        match self {
+            Self::Checkboxes { attributes, .. } => Some(&attributes.label),
+            Self::Dropdown { attributes, .. } => Some(&attributes.label),
+            Self::Input { attributes, .. } => Some(&attributes.label),
+            Self::Textarea { attributes, .. } => Some(&attributes.label),
+            Self::Markdown { .. } => None,
+        }
+    }
+}
+
+/// Identifies the elements in the preview, i.e. their HTML ids and the keys of their answers.
+/// Elements without an id fall back to a slug of their label,
+/// which stays the same when other elements are added or reordered.
+/// A slug already taken gets a numbered suffix, and an empty one becomes `field-<index>`.
+fn anchors(body: &[BodyType]) -> Vec<Option<String>> {
+    let mut taken = body
+        .iter()
+        .filter_map(|item| item.id().map(str::to_string))
+        .collect::<HashSet<_>>();
+    body.iter()
+        .enumerate()
+        .map(|(index, item)| {
+            if let Some(id) = item.id() {
+                return Some(id.to_string());
+            }
+            let slug = match slugify(item.label()?) {
+                slug if slug.is_empty() => format!("field-{index}"),
+                slug => slug,
+            };
+            let mut anchor = slug.clone();
+            let mut count = 1;
+            while !taken.insert(anchor.clone()) {
+                count += 1;
+                anchor = format!("{slug}-{count}");
+            }
+            Some(anchor)
+        })
+        .collect()
+}
+
 impl Render for BodyType {
     fn render(&self) -> Markup {
+        let anchor = anchors(std::slice::from_ref(self)).remove(0);
+        self.to_html(anchor.as_deref().unwrap_or_default(), None)
+    }
+}
+
+impl BodyType {
+    /// Renders the element identified by `id` with `prefill` as its value,
+    /// as if it were given in the query string.
+    fn to_html(&self, id: &str, prefill: Option<&str>) -> Markup {
         match self {
-            BodyType::Checkboxes { id, attributes } => {
+            BodyType::Checkboxes { attributes, .. } => {
                 html! {
                     div #(id) {
                         label {
-                            h3 {(attributes.label)}
+                            h3 required=(required_marker(false)) {(attributes.label)}
                         }
                     }
                     div."body-description" {(attributes.description)}
                     div {
-                        @for option in &attributes.options {
+                        @for (index, option) in attributes.options.iter().enumerate() {
                             div {
-                                input type="checkbox" disabled="disabled" value=(option.label.render().into_string());
+                                input
+                                    type="checkbox"
+                                    name=(format!("issue-form[{}]", id))
+                                    value=(index)
+                                    checked[is_selected(prefill, &option.label.0)];
                                 label."checkbox-label" {(option.label)}
                                 @if option.required { span."checkbox-required" {"*"} }
                             }
@@ -162,11 +327,24 @@ impl Render for BodyType {
                 }
             }
             BodyType::Dropdown {
-                id,
                 attributes,
                 validations,
+                ..
             } => {
-                let required = is_required(validations);
+                let required = required_marker(Validations::is_required(validations));
+                // A prefilled value replaces the default selection rather than adding to it.
+                let checked = |index: usize, option: &str| match prefill {
+                    Some(_) => is_selected(prefill, option),
+                    None => attributes.default == Some(index),
+                };
+                let selection = attributes
+                    .options
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, option)| checked(*index, option))
+                    .map(|(_, option)| option.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ");
                 html! {
                     div #(id) {
                         label {
@@ -175,15 +353,16 @@ impl Render for BodyType {
                     }
                     div."body-description" {(attributes.description)}
                     details ."dropdown-container" {
-                        summary role="button" {"Selection: "}
+                        summary role="button" {"Selection: " (selection)}
                         div.choices {
-                            @for option in &attributes.options {
+                            @for (index, option) in attributes.options.iter().enumerate() {
                                 label."checkbox-label" {
                                     input
                                         type=(if attributes.multiple {"checkbox"} else {"radio"})
                                         name=(format!("issue-form[{}]", id))
                                         hidden
-                                        value=(option);
+                                        value=(option)
+                                        checked[checked(index, option)];
                                     div.checkmark {"✓"}
                                     div {(option)}
                                 }
@@ -193,11 +372,11 @@ impl Render for BodyType {
                 }
             }
             BodyType::Input {
-                id,
                 attributes,
                 validations,
+                ..
             } => {
-                let required = is_required(validations);
+                let required = required_marker(Validations::is_required(validations));
                 html! {
                     div #(id) {
                         label {
@@ -205,20 +384,20 @@ impl Render for BodyType {
                         }
                     }
                     div."body-description" {(attributes.description)}
-                    input."form-input" type="text" disabled="disabled" placeholder=(attributes.placeholder) value=[(&attributes.value)];
+                    input."form-input" type="text" name=(format!("issue-form[{}]", id)) placeholder=(attributes.placeholder) value=[prefill.or(attributes.value.as_deref())];
                 }
             }
-            BodyType::Markdown { attributes } => {
+            BodyType::Markdown { attributes, .. } => {
                 html! {
                     div."markdown-description" {(attributes.value)}
                 }
             }
             BodyType::Textarea {
-                id,
                 attributes,
                 validations,
+                ..
             } => {
-                let required = is_required(validations);
+                let required = required_marker(Validations::is_required(validations));
                 html! {
                     div #(id) {
                         label {
@@ -226,13 +405,33 @@ impl Render for BodyType {
                         }
                     }
                     div."body-description" {(attributes.description)}
-                    textarea."form-textarea" disabled="disabled" placeholder=(attributes.placeholder) lang=[(&attributes.render)] {(attributes.value)}
+                    textarea."form-textarea".render[attributes.render.is_some()] name=(format!("issue-form[{}]", id)) placeholder=(attributes.placeholder) {(prefill.unwrap_or(&attributes.value))}
+                    @if attributes.render.is_some() {
+                        p."render-note" {"This will be automatically formatted into code, so no need for backticks."}
+                    }
                 }
             }
         }
     }
 }
 
+/// Lowercases `label` and joins its alphanumeric words with hyphens.
+fn slugify(label: &str) -> String {
+    label
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Whether `choice` is among the comma-separated values of `prefill`.
+fn is_selected(prefill: Option<&str>, choice: &str) -> bool {
+    prefill.is_some_and(|prefill| {
+        prefill == choice || prefill.split(',').any(|value| value.trim() == choice)
+    })
+}
+
 #[derive(Debug, Deserialize)]
 struct CheckboxesAttribute {
     label: String,
@@ -256,6 +455,8 @@ struct DropdownAttribute {
     #[serde(default = "default_false")]
     multiple: bool,
     options: Vec<String>,
+    /// Index of the option selected beforehand.
+    default: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -285,20 +486,28 @@ struct TextareaAttribute {
     render: Option<String>,
 }
 
+/// Validations of inputs, textareas and dropdowns, all of which GitHub lets only be required.
 #[derive(Debug, Deserialize)]
 struct Validations {
     #[serde(default = "default_false")]
     required: bool,
 }
 
+impl Validations {
+    fn is_required(validations: &Option<Self>) -> bool {
+        validations
+            .as_ref()
+            .is_some_and(|validations| validations.required)
+    }
+}
+
 #[derive(Debug, Deserialize)]
-struct Markdown(String);
+pub struct Markdown(pub String);
 
 impl Render for Markdown {
     fn render(&self) -> Markup {
         let mut output = String::new();
-        let parser = Parser::new(self.0.as_ref());
-        html::push_html(&mut output, parser);
+        html::push_html(&mut output, gfm::parse(&self.0).into_iter());
         PreEscaped(output)
     }
 }
@@ -312,12 +521,13 @@ impl Render for MarkdownInline {
         // The filter method strips off the outermost paragraph element.
         // This is required to render checkbox labels properly.
         // cf. https://github.com/raphlinus/pulldown-cmark/issues/543
-        let parser = Parser::new(self.0.as_ref()).filter(|event| match event {
-            Event::Start(Tag::Paragraph) => false,
-            Event::End(Tag::Paragraph) => false,
-            _ => true,
+        let events = gfm::parse(&self.0).into_iter().filter(|event| {
+            !matches!(
+                event,
+                Event::Start(Tag::Paragraph) | Event::End(Tag::Paragraph)
+            )
         });
-        html::push_html(&mut output, parser);
+        html::push_html(&mut output, events);
         PreEscaped(output)
     }
 }
@@ -338,8 +548,9 @@ fn default_empty_sequecelike() -> SequenceLike {
     SequenceLike::Sequence(vec![])
 }
 
-fn is_required(validations: &Option<Validations>) -> &str {
-    if let Some(Validations { required: true }) = validations {
+/// Value of the `required` attribute the stylesheet marks element labels by.
+fn required_marker(required: bool) -> &'static str {
+    if required {
         "required"
     } else {
         "optional"
@@ -354,7 +565,7 @@ mod unit_test {
     #[test]
     fn checkbox() {
         let body = BodyType::Checkboxes {
-            id: "operating-systems".to_string(),
+            id: Some("operating-systems".to_string()),
             attributes: CheckboxesAttribute {
                 label: "Which operating systems have you used?".to_string(),
                 description: Markdown("You may select more than one.".to_string()),
@@ -373,18 +584,19 @@ mod unit_test {
                     },
                 ],
             },
+            validations: None,
         };
         assert_eq!(
             &body.render().into_string(),
-            r#"<div id="operating-systems"><label><h3>Which operating systems have you used?</h3></label></div><div class="body-description"><p>You may select more than one.</p>
-</div><div><div><input type="checkbox" disabled="disabled" value="macOS."><label class="checkbox-label">macOS.</label></div><div><input type="checkbox" disabled="disabled" value="Windows."><label class="checkbox-label">Windows.</label></div><div><input type="checkbox" disabled="disabled" value="Linux."><label class="checkbox-label">Linux.</label></div></div>"#
+            r#"<div id="operating-systems"><label><h3 required="optional">Which operating systems have you used?</h3></label></div><div class="body-description"><p>You may select more than one.</p>
+</div><div><div><input type="checkbox" name="issue-form[operating-systems]" value="0"><label class="checkbox-label">macOS.</label></div><div><input type="checkbox" name="issue-form[operating-systems]" value="1"><label class="checkbox-label">Windows.</label></div><div><input type="checkbox" name="issue-form[operating-systems]" value="2"><label class="checkbox-label">Linux.</label></div></div>"#
         )
     }
 
     #[test]
     fn dropdown() {
         let body = BodyType::Dropdown {
-            id: "download".to_string(),
+            id: Some("download".to_string()),
             attributes: DropdownAttribute {
                 label: "dropdown-test".to_string(),
                 description: Markdown("How did you download the software?".to_string()),
@@ -395,6 +607,7 @@ mod unit_test {
                     "apt-get".to_string(),
                     "Built from source".to_string(),
                 ],
+                default: None,
             },
             validations: Some(Validations { required: true }),
         };
@@ -408,7 +621,7 @@ mod unit_test {
     #[test]
     fn input() {
         let body = BodyType::Input {
-            id: "prevalence".to_string(),
+            id: Some("prevalence".to_string()),
             attributes: InputAttribute {
                 label: "Bug prevalence".to_string(),
                 description: Markdown("How often do you or others encounter this bug?".to_string()),
@@ -422,7 +635,7 @@ mod unit_test {
         assert_eq!(
             &body.render().into_string(),
             r#"<div id="prevalence"><label><h3 required="required">Bug prevalence</h3></label></div><div class="body-description"><p>How often do you or others encounter this bug?</p>
-</div><input class="form-input" type="text" disabled="disabled" placeholder="Example: Whenever I visit the personal account page (1-2 times a week)">"#
+</div><input class="form-input" type="text" name="issue-form[prevalence]" placeholder="Example: Whenever I visit the personal account page (1-2 times a week)">"#
         )
     }
 
@@ -432,18 +645,19 @@ mod unit_test {
             attributes: MarkdownAttribute {
                 value: Markdown("## Thank you for contributing to our project!".to_string()),
             },
+            validations: None,
         };
         assert_eq!(
             &body.render().into_string(),
-            r#"<div class="markdown-description"><h2>Thank you for contributing to our project!</h2>
-</div>"#
+            r##"<div class="markdown-description"><h2 id="user-content-thank-you-for-contributing-to-our-project"><a class="anchor" aria-hidden="true" href="#user-content-thank-you-for-contributing-to-our-project"></a>Thank you for contributing to our project!</h2>
+</div>"##
         )
     }
 
     #[test]
     fn textarea() {
         let body = BodyType::Textarea {
-            id: "repro".to_string(),
+            id: Some("repro".to_string()),
             attributes: TextareaAttribute {
                 label: "Reproduction steps".to_string(),
                 description: Markdown(
@@ -459,7 +673,145 @@ mod unit_test {
         assert_eq!(
             &body.render().into_string(),
             r#"<div id="repro"><label><h3 required="required">Reproduction steps</h3></label></div><div class="body-description"><p>How do you trigger this bug? Please walk us through it step by step.</p>
-</div><textarea class="form-textarea" disabled="disabled" placeholder=""></textarea>"#
+</div><textarea class="form-textarea" name="issue-form[repro]" placeholder=""></textarea>"#
+        )
+    }
+
+    #[test]
+    fn render_textarea() {
+        let body: BodyType = serde_yaml::from_str(
+            r#"
+type: textarea
+id: logs
+attributes:
+  label: Logs
+  render: shell
+"#,
+        )
+        .unwrap();
+        let html = body.render().into_string();
+        assert!(html.contains(r#"<textarea class="form-textarea render" name="issue-form[logs]""#));
+        assert!(html.contains(
+            r#"<p class="render-note">This will be automatically formatted into code, so no need for backticks.</p>"#
+        ));
+    }
+
+    #[test]
+    fn fallback_anchor() {
+        let body: BodyType = serde_yaml::from_str(
+            r#"
+type: input
+attributes:
+  label: "What's the version?"
+"#,
+        )
+        .unwrap();
+        assert_eq!(body.id(), None);
+        assert_eq!(
+            &body.render().into_string(),
+            r#"<div id="what-s-the-version"><label><h3 required="optional">What's the version?</h3></label></div><div class="body-description"></div><input class="form-input" type="text" name="issue-form[what-s-the-version]" placeholder="">"#
+        )
+    }
+
+    #[test]
+    fn anchor_collisions() {
+        let body: Vec<BodyType> = serde_yaml::from_str(
+            r#"
+- type: input
+  id: version
+  attributes:
+    label: App version
+- type: input
+  attributes:
+    label: Version
+- type: input
+  attributes:
+    label: Version?
+- type: input
+  attributes:
+    label: "???"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            anchors(&body),
+            vec![
+                Some("version".to_string()),
+                Some("version-2".to_string()),
+                Some("version-3".to_string()),
+                Some("field-3".to_string()),
+            ]
+        );
+        let html = render_body(&body, &Prefill::default()).into_string();
+        assert!(html.contains(r#"<div id="version-2">"#));
+        assert!(html.contains(r#"name="issue-form[field-3]""#));
+    }
+
+    #[test]
+    fn title() {
+        let form: Form = serde_yaml::from_str(
+            r#"
+name: Bug
+description: Report a bug
+title: "[Bug]: "
+body:
+  - type: input
+    attributes:
+      label: Version
+"#,
+        )
+        .unwrap();
+        assert!(form
+            .summarize("bug.yml")
+            .into_string()
+            .contains(r#"<div class="title">Title: <code>[Bug]: </code></div>"#));
+        assert!(form
+            .to_html(&[], &Prefill::default(), Mode::Static)
+            .into_string()
+            .contains(r#"name="issue-title" placeholder="Title" value="[Bug]: ""#));
+    }
+
+    #[test]
+    fn type_and_projects() {
+        let form: Form = serde_yaml::from_str(
+            r#"
+name: Bug
+description: Report a bug
+type: Bug
+projects: ["octo-org/1", "octo-org/44"]
+body:
+  - type: input
+    attributes:
+      label: Version
+"#,
+        )
+        .unwrap();
+        let html = form
+            .to_html(&[], &Prefill::default(), Mode::Static)
+            .into_string();
+        assert!(html.contains(r#"<th align="left">Assignees</th><th align="left">Type</th><th align="left">Projects</th>"#));
+        assert!(html
+            .contains(r#"<td align="left">Bug</td><td align="left">octo-org/1, octo-org/44</td>"#));
+    }
+
+    #[test]
+    fn dropdown_default() {
+        let body: BodyType = serde_yaml::from_str(
+            r#"
+type: dropdown
+id: os
+attributes:
+  label: OS
+  options: [Linux, macOS]
+  default: 1
+"#,
         )
+        .unwrap();
+        let html = body.to_html("os", None).into_string();
+        assert!(html.contains(r#"<summary role="button">Selection: macOS</summary>"#));
+        assert!(html.contains(r#"value="macOS" checked"#));
+        let html = body.to_html("os", Some("Linux")).into_string();
+        assert!(html.contains(r#"<summary role="button">Selection: Linux</summary>"#));
+        assert!(!html.contains(r#"value="macOS" checked"#));
     }
 }
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use anyhow::Result;
+use maud::{html, Markup};
+use serde::Deserialize;
+
+use super::{label_chips, render_body, schema, validate, BodyType, Prefill, SequenceLike};
+use crate::{
+    diagnostic::{self, Diagnostic},
+    layout::{self, Mode},
+    yaml::Document,
+};
+
+/// Reads and validates the discussion category form at `file`.
+/// Keys the form does not know of are reported as well if `strict` is set.
+pub fn load(file: impl AsRef<Path>, strict: bool) -> Result<(Option<Discussion>, Vec<Diagnostic>)> {
+    let document = Document::open(file)?;
+    let (form, mut diagnostics) = document.load(Discussion::validate);
+    diagnostics.splice(0..0, document.lint(&schema::DISCUSSION, strict));
+    Ok((form, diagnostics))
+}
+
+/// Slug of the category the form at `file_name` applies to, which GitHub takes from the file name.
+pub fn category(file_name: &str) -> &str {
+    file_name
+        .strip_suffix(".yml")
+        .or_else(|| file_name.strip_suffix(".yaml"))
+        .unwrap_or(file_name)
+}
+
+/// Discussion category form, which has no name or description unlike issue forms
+/// since the category it belongs to tells what it is for.
+// cf. https://docs.github.com/en/discussions/managing-discussions-for-your-community/syntax-for-discussion-category-forms
+#[derive(Debug, Deserialize)]
+pub struct Discussion {
+    title: Option<String>,
+    #[serde(default)]
+    labels: SequenceLike,
+    body: Vec<BodyType>,
+}
+
+impl Discussion {
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        validate::validate_body(&self.body)
+    }
+
+    pub fn to_html(&self, category: &str, diagnostics: &[Diagnostic], mode: Mode) -> Markup {
+        layout::page(
+            mode,
+            html! {
+                (diagnostic::render_all(diagnostics))
+                article {
+                    table role="table" {
+                        thead {
+                            tr {
+                                th align="left" {"Category"}
+                                th align="left" {"Labels"}
+                            }
+                        }
+                        tbody {
+                            tr {
+                                td align="left" {code {(category)}}
+                                td align="left" {(self.labels.join())}
+                            }
+                        }
+                    }
+                }
+                form {
+                    div."issue-title" {
+                        label {
+                            h3 required="required" {"Title"}
+                        }
+                        input."form-input" type="text" name="discussion-title" placeholder="Title" value=[&self.title];
+                    }
+                    (render_body(&self.body, &Prefill::default()))
+                }
+            },
+        )
+    }
+
+    pub fn summarize(&self, category: &str, link: &str) -> Markup {
+        html! {
+            div.summary {
+                div {
+                    strong.name {"Category " code {(category)}}
+                    (label_chips(&self.labels.items()))
+                    @if let Some(title) = &self.title {
+                        div.title {"Title: " code {(title)}}
+                    }
+                }
+                a.button href=(link) {"Preview"}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn discussion() {
+        let form: Discussion = serde_yaml::from_str(
+            r#"
+title: "[Idea] "
+labels: [enhancement]
+body:
+  - type: textarea
+    id: idea
+    attributes:
+      label: Idea
+"#,
+        )
+        .unwrap();
+        assert_eq!(form.validate(), vec![]);
+        assert_eq!(category("ideas.yml"), "ideas");
+        let html = form.to_html("ideas", &[], Mode::Static).into_string();
+        assert!(html.contains("<td align=\"left\"><code>ideas</code></td>"));
+        assert!(html.contains(r#"name="discussion-title" placeholder="Title" value="[Idea] ""#));
+        assert!(html.contains(r#"name="issue-form[idea]""#));
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use pulldown_cmark::{CowStr, Event, LinkType, Options, Parser, Tag};
+
+/// Alerts GitHub renders from blockquotes starting with `[!TYPE]`, with their titles.
+// cf. https://docs.github.com/en/get-started/writing-on-github/getting-started-with-writing-and-formatting-on-github/basic-writing-and-formatting-syntax#alerts
+const ALERTS: [(&str, &str); 5] = [
+    ("NOTE", "Note"),
+    ("TIP", "Tip"),
+    ("IMPORTANT", "Important"),
+    ("WARNING", "Warning"),
+    ("CAUTION", "Caution"),
+];
+
+/// Parses `source` as GitHub Flavored Markdown, which pulldown-cmark covers except for
+/// extended autolinks, alerts and heading anchors that are added on top of its events.
+// cf. https://github.github.com/gfm/
+pub(super) fn parse(source: &str) -> Vec<Event<'_>> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let events = merge_text(Parser::new_ext(source, options));
+    let events = autolink(events);
+    let events = alerts(events);
+    anchor_headings(events)
+}
+
+/// Joins adjacent text events, which pulldown-cmark splits at characters that might start markup.
+fn merge_text<'a>(events: impl Iterator<Item = Event<'a>>) -> Vec<Event<'a>> {
+    let mut merged: Vec<Event> = vec![];
+    for event in events {
+        match (merged.last_mut(), event) {
+            (Some(Event::Text(previous)), Event::Text(text)) => {
+                *previous = CowStr::from(format!("{previous}{text}"));
+            }
+            (_, event) => merged.push(event),
+        }
+    }
+    merged
+}
+
+/// Links bare URLs and `www.` domains in text outside links and code.
+fn autolink(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut linked = vec![];
+    let mut depth = 0;
+    for event in events {
+        match event {
+            Event::Start(Tag::Link(..) | Tag::Image(..) | Tag::CodeBlock(_)) => {
+                depth += 1;
+                linked.push(event);
+            }
+            Event::End(Tag::Link(..) | Tag::Image(..) | Tag::CodeBlock(_)) => {
+                depth -= 1;
+                linked.push(event);
+            }
+            Event::Text(text) if depth == 0 => {
+                let mut rest = &*text;
+                while let Some((start, end)) = find_url(rest) {
+                    let url = &rest[start..end];
+                    let href = if url.starts_with("www.") {
+                        format!("http://{url}")
+                    } else {
+                        url.to_string()
+                    };
+                    if start > 0 {
+                        linked.push(Event::Text(rest[..start].to_string().into()));
+                    }
+                    let tag = Tag::Link(LinkType::Autolink, href.into(), "".into());
+                    linked.push(Event::Start(tag.clone()));
+                    linked.push(Event::Text(url.to_string().into()));
+                    linked.push(Event::End(tag));
+                    rest = &rest[end..];
+                }
+                if !rest.is_empty() {
+                    linked.push(Event::Text(rest.to_string().into()));
+                }
+            }
+            event => linked.push(event),
+        }
+    }
+    linked
+}
+
+/// Finds the first extended autolink in `text` as a byte range.
+// cf. https://github.github.com/gfm/#autolinks-extension-
+fn find_url(text: &str) -> Option<(usize, usize)> {
+    let start = text.char_indices().find_map(|(index, _)| {
+        let preceded = text[..index]
+            .chars()
+            .next_back()
+            .is_none_or(|c| c.is_whitespace() || "*_~(".contains(c));
+        let scheme = ["https://", "http://", "www."]
+            .iter()
+            .find(|scheme| text[index..].starts_with(*scheme))?;
+        (preceded && text[index + scheme.len()..].starts_with(|c: char| c.is_alphanumeric()))
+            .then_some(index)
+    })?;
+    let mut end = text[start..]
+        .find(|c: char| c.is_whitespace() || c == '<')
+        .map_or(text.len(), |length| start + length);
+    // Trailing punctuation and unbalanced parentheses are taken to end the sentence instead.
+    loop {
+        let url = &text[start..end];
+        match url.chars().next_back() {
+            Some(c) if "?!.,:*_~'\"".contains(c) => end -= 1,
+            Some(')') if url.matches(')').count() > url.matches('(').count() => end -= 1,
+            _ => return Some((start, end)),
+        }
+    }
+}
+
+/// Turns blockquotes whose first line is only `[!TYPE]` into alerts.
+fn alerts(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut rendered = vec![];
+    // Whether each open blockquote is an alert, to close it with the right tag.
+    let mut blockquotes = vec![];
+    let mut index = 0;
+    while index < events.len() {
+        match &events[index] {
+            Event::Start(Tag::BlockQuote) => {
+                let alert = match events.get(index + 1..index + 4) {
+                    Some([Event::Start(Tag::Paragraph), Event::Text(text), next])
+                        if matches!(next, Event::SoftBreak | Event::End(Tag::Paragraph)) =>
+                    {
+                        alert_title(text).map(|title| (title, next))
+                    }
+                    _ => None,
+                };
+                match alert {
+                    Some((title, next)) => {
+                        rendered.push(Event::Html(
+                            format!(
+                                "<div class=\"markdown-alert markdown-alert-{}\">\n<p class=\"markdown-alert-title\">{title}</p>\n",
+                                title.to_lowercase()
+                            )
+                            .into(),
+                        ));
+                        // The rest of the first paragraph stays, unless the marker is all of it.
+                        if matches!(next, Event::SoftBreak) {
+                            rendered.push(Event::Start(Tag::Paragraph));
+                        }
+                        index += 4;
+                        blockquotes.push(true);
+                        continue;
+                    }
+                    None => blockquotes.push(false),
+                }
+            }
+            // Popped in the guard, so a plain blockquote is closed by the arm below.
+            Event::End(Tag::BlockQuote) if blockquotes.pop() == Some(true) => {
+                rendered.push(Event::Html("</div>\n".into()));
+                index += 1;
+                continue;
+            }
+            _ => {}
+        }
+        rendered.push(events[index].clone());
+        index += 1;
+    }
+    rendered
+}
+
+fn alert_title(marker: &str) -> Option<&'static str> {
+    let kind = marker.trim().strip_prefix("[!")?.strip_suffix(']')?;
+    ALERTS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(kind))
+        .map(|(_, title)| *title)
+}
+
+/// Gives headings the ids GitHub links them by, made unique within the document.
+fn anchor_headings(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut anchored = vec![];
+    let mut seen = HashMap::new();
+    let mut events = events.into_iter();
+    while let Some(event) = events.next() {
+        let level = match event {
+            Event::Start(Tag::Heading(level, ..)) => level,
+            event => {
+                anchored.push(event);
+                continue;
+            }
+        };
+        let mut content = vec![];
+        let mut text = String::new();
+        for event in events.by_ref() {
+            match &event {
+                Event::End(Tag::Heading(..)) => {
+                    content.push(event);
+                    break;
+                }
+                Event::Text(fragment) | Event::Code(fragment) => text.push_str(fragment),
+                _ => {}
+            }
+            content.push(event);
+        }
+        let mut slug = slug(&text);
+        let count: &mut usize = seen.entry(slug.clone()).or_default();
+        if *count > 0 {
+            slug = format!("{slug}-{count}");
+        }
+        *count += 1;
+        anchored.push(Event::Html(
+            format!(
+                "<{level} id=\"user-content-{slug}\"><a class=\"anchor\" aria-hidden=\"true\" href=\"#user-content-{slug}\"></a>"
+            )
+            .into(),
+        ));
+        anchored.extend(content);
+    }
+    anchored
+}
+
+/// Lowercases `text`, drops punctuation and replaces spaces with hyphens like GitHub does.
+fn slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_')
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn render(source: &str) -> String {
+        let mut output = String::new();
+        pulldown_cmark::html::push_html(&mut output, parse(source).into_iter());
+        output
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(
+            render("| a |\n| - |\n| ~~b~~ |\n\n- [x] done"),
+            "<table><thead><tr><th>a</th></tr></thead><tbody>\n<tr><td><del>b</del></td></tr>\n</tbody></table>\n<ul>\n<li><input disabled=\"\" type=\"checkbox\" checked=\"\"/>\ndone</li>\n</ul>\n"
+        );
+        assert_eq!(
+            render("See www.github.com, https://example.com/a_(b)). and `https://no`"),
+            "<p>See <a href=\"http://www.github.com\">www.github.com</a>, <a href=\"https://example.com/a_(b)\">https://example.com/a_(b)</a>). and <code>https://no</code></p>\n"
+        );
+    }
+
+    #[test]
+    fn alerts() {
+        assert_eq!(
+            render("> [!note]\n> Read *this*.\n\n> [!WARNING]\n>\n> Careful\n\n> [!NOPE]"),
+            "<div class=\"markdown-alert markdown-alert-note\">\n<p class=\"markdown-alert-title\">Note</p>\n<p>Read <em>this</em>.</p>\n</div>\n<div class=\"markdown-alert markdown-alert-warning\">\n<p class=\"markdown-alert-title\">Warning</p>\n<p>Careful</p>\n</div>\n<blockquote>\n<p>[!NOPE]</p>\n</blockquote>\n"
+        );
+    }
+
+    #[test]
+    fn heading_anchors() {
+        assert_eq!(
+            render("## What's `new`?\n## What's new"),
+            "<h2 id=\"user-content-whats-new\"><a class=\"anchor\" aria-hidden=\"true\" href=\"#user-content-whats-new\"></a>What's <code>new</code>?</h2>\n<h2 id=\"user-content-whats-new-1\"><a class=\"anchor\" aria-hidden=\"true\" href=\"#user-content-whats-new-1\"></a>What's new</h2>\n"
+        );
+    }
+}
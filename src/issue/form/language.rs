@@ -0,0 +1,37 @@
+/// Names and aliases of the languages GitHub highlights, one language per line as `Name: aliases`.
+const LANGUAGES: &str = include_str!("languages.txt");
+
+/// Every spelling Linguist resolves to a language, lowercased as it matches them regardless of case.
+/// Names are also accepted with hyphens for spaces, e.g. `emacs-lisp`.
+fn spellings() -> impl Iterator<Item = String> {
+    LANGUAGES
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            line.split_once(": ")
+                .or_else(|| Some((line.strip_suffix(':')?, "")))
+        })
+        .flat_map(|(name, aliases)| {
+            let name = name.to_lowercase();
+            [name.replace(' ', "-"), name]
+                .into_iter()
+                .chain(aliases.split(", ").map(str::to_lowercase))
+        })
+        .filter(|spelling| !spelling.is_empty())
+}
+
+/// Whether GitHub can format a textarea as `language`.
+pub(super) fn is_known(language: &str) -> bool {
+    let language = language.to_lowercase();
+    spellings().any(|spelling| spelling == language)
+}
+
+/// The spelling closest to a mistyped `language`, if any is close enough.
+pub(super) fn suggest(language: &str) -> Option<String> {
+    let spellings = spellings().collect::<Vec<_>>();
+    crate::yaml::suggest(
+        &language.to_lowercase(),
+        spellings.iter().map(String::as_str),
+    )
+    .map(str::to_string)
+}
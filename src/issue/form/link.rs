@@ -0,0 +1,135 @@
+use maud::{html, Markup};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use super::{BodyType, Form, Prefill};
+use crate::{
+    diagnostic::{self, Diagnostic},
+    layout::{self, Mode},
+};
+
+/// Characters left as they are in query strings, i.e. the unreserved ones of RFC 3986.
+pub(crate) const QUERY: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+impl Prefill {
+    /// Builds the link to a new issue in `repo` (`owner/name`) from the `template` form.
+    pub fn to_url(&self, repo: &str, template: &str) -> String {
+        let mut query = vec![("template", template.to_string())];
+        if let Some(title) = &self.title {
+            query.push(("title", title.clone()));
+        }
+        if !self.labels.is_empty() {
+            query.push(("labels", self.labels.join(",")));
+        }
+        if !self.assignees.is_empty() {
+            query.push(("assignees", self.assignees.join(",")));
+        }
+        query.extend(
+            self.fields
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.clone())),
+        );
+        let query = query
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    utf8_percent_encode(key, QUERY),
+                    utf8_percent_encode(value, QUERY)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        format!("https://github.com/{repo}/issues/new?{query}")
+    }
+}
+
+impl Form {
+    /// Renders a page that builds prefill links to the `template` form in `repo`.
+    pub fn to_link_html(
+        &self,
+        template: &str,
+        repo: &str,
+        prefill: &Prefill,
+        diagnostics: &[Diagnostic],
+        mode: Mode,
+    ) -> Markup {
+        layout::page(
+            mode,
+            html! {
+                h2 {"Prefill link for " (template)}
+                form.link method="get" {
+                    input type="hidden" name="template" value=(template);
+                    label {
+                        h3 {"Repository"}
+                        input."form-input" type="text" name="repo" placeholder="owner/name" value=(repo);
+                    }
+                    label {
+                        h3 {"Title"}
+                        input."form-input" type="text" name="title" value=[&prefill.title];
+                    }
+                    label {
+                        h3 {"Labels"}
+                        input."form-input" type="text" name="labels" value=(prefill.labels.join(","));
+                    }
+                    label {
+                        h3 {"Assignees"}
+                        input."form-input" type="text" name="assignees" value=(prefill.assignees.join(","));
+                    }
+                    @for item in &self.body {
+                        @if let (Some(id), Some(label)) = (item.id(), item.label()) {
+                            label {
+                                h3 {(label) " " code {(id)}}
+                                @if let Some(options) = dropdown_options(item) {
+                                    select name=(id) {
+                                        option value="" {}
+                                        @for option in options {
+                                            option value=(option) selected[prefill.field(id) == Some(option)] {(option)}
+                                        }
+                                    }
+                                } @else {
+                                    input."form-input" type="text" name=(id) value=[prefill.field(id)];
+                                }
+                            }
+                        }
+                    }
+                    button."submit-button" type="submit" {"Build link"}
+                }
+                @if !repo.is_empty() {
+                    h3 {"Link"}
+                    pre {(prefill.to_url(repo, template))}
+                }
+                (diagnostic::render_all(diagnostics))
+            },
+        )
+    }
+}
+
+fn dropdown_options(item: &BodyType) -> Option<&[String]> {
+    match item {
+        BodyType::Dropdown { attributes, .. } => Some(&attributes.options),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_url() {
+        let prefill = Prefill::from_query(vec![
+            ("title".to_string(), "[Bug]: crash on start".to_string()),
+            ("labels".to_string(), "bug,needs triage".to_string()),
+            ("version".to_string(), "1.2 & later".to_string()),
+        ]);
+        assert_eq!(
+            prefill.to_url("yudai-nkt/gh-form", "bug.yml"),
+            "https://github.com/yudai-nkt/gh-form/issues/new?template=bug.yml&title=%5BBug%5D%3A%20crash%20on%20start&labels=bug%2Cneeds%20triage&version=1.2%20%26%20later"
+        );
+    }
+}
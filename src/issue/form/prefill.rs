@@ -0,0 +1,217 @@
+use std::collections::HashSet;
+
+use maud::{html, Markup};
+
+use super::{BodyType, Form};
+use crate::diagnostic::{Diagnostic, Rule};
+
+/// Query parameters GitHub understands on `issues/new` besides field ids.
+const RESERVED: [&str; 7] = [
+    "template",
+    "title",
+    "labels",
+    "assignees",
+    "milestone",
+    "projects",
+    "body",
+];
+
+/// Values given in the query string of a link to a new issue.
+// cf. https://docs.github.com/en/issues/tracking-your-work-with-issues/creating-an-issue#creating-an-issue-from-a-url-query
+#[derive(Debug, Default)]
+pub struct Prefill {
+    pub title: Option<String>,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    /// Values keyed by element id, in the order they were given.
+    pub(super) fields: Vec<(String, String)>,
+}
+
+impl Prefill {
+    pub fn from_query(query: Vec<(String, String)>) -> Self {
+        let mut prefill = Self::default();
+        for (key, value) in query {
+            match &*key {
+                "title" => prefill.title = Some(value),
+                "labels" => prefill.labels.extend(split(&value)),
+                "assignees" => prefill.assignees.extend(split(&value)),
+                _ if RESERVED.contains(&&*key) => {}
+                _ => prefill.fields.push((key, value)),
+            }
+        }
+        prefill
+    }
+
+    pub fn field(&self, id: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .rev()
+            .find(|(key, _)| key == id)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn split(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+}
+
+impl Form {
+    /// Warns about prefilled fields that GitHub would silently ignore.
+    pub fn validate_prefill(&self, prefill: &Prefill) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        let mut seen = HashSet::new();
+        for (key, _) in &prefill.fields {
+            if !seen.insert(key) {
+                continue;
+            }
+            // Later parameters win, as they do on GitHub.
+            let value = prefill.field(key).unwrap_or_default();
+            match self.body.iter().find(|item| item.id() == Some(key)) {
+                None => diagnostics.push(Diagnostic::new(
+                    Rule::UnknownPrefill,
+                    "",
+                    format!("query parameter `{key}` does not match the id of any element"),
+                )),
+                Some(BodyType::Dropdown { attributes, .. }) => {
+                    let values = if attributes.multiple {
+                        split(value).collect()
+                    } else {
+                        vec![value.to_string()]
+                    };
+                    for value in values {
+                        if !attributes.options.contains(&value) {
+                            diagnostics.push(Diagnostic::new(
+                                Rule::UnknownPrefillOption,
+                                "",
+                                format!("`{value}` is not an option of dropdown `{key}`"),
+                            ));
+                        }
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        diagnostics
+    }
+
+    /// Shows the title, labels and assignees the new issue would get.
+    pub(super) fn render_prefill(&self, prefill: &Prefill) -> Markup {
+        let mut labels = self.labels.items();
+        let mut assignees = self.assignees.items();
+        for label in &prefill.labels {
+            if !labels.contains(label) {
+                labels.push(label.clone());
+            }
+        }
+        for assignee in &prefill.assignees {
+            if !assignees.contains(assignee) {
+                assignees.push(assignee.clone());
+            }
+        }
+        html! {
+            @if prefill.title.is_some() || !prefill.labels.is_empty() || !prefill.assignees.is_empty() {
+                div.prefill {
+                    h4 {"Applied from the query string"}
+                    dl {
+                        @if let Some(title) = &prefill.title {
+                            dt {"Title"}
+                            dd {(title)}
+                        }
+                        dt {"Labels"}
+                        dd {(labels.join(", "))}
+                        dt {"Assignees"}
+                        dd {(assignees.join(", "))}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn form() -> Form {
+        serde_yaml::from_str(
+            r#"
+name: Bug
+description: Report a bug
+labels: [bug]
+body:
+  - type: input
+    id: version
+    attributes:
+      label: Version
+  - type: dropdown
+    id: os
+    attributes:
+      label: OS
+      options: [Linux, macOS]
+"#,
+        )
+        .unwrap()
+    }
+
+    fn messages(form: &Form, prefill: &Prefill) -> Vec<String> {
+        form.validate_prefill(prefill)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn prefill() {
+        let form = form();
+        let prefill = Prefill::from_query(vec![
+            ("template".to_string(), "bug.yml".to_string()),
+            ("labels".to_string(), "bug,triage".to_string()),
+            ("version".to_string(), "1.2".to_string()),
+            ("os".to_string(), "macOS".to_string()),
+            ("verison".to_string(), "1.2".to_string()),
+        ]);
+        assert_eq!(prefill.labels, vec!["bug", "triage"]);
+        assert_eq!(prefill.field("version"), Some("1.2"));
+
+        let html = form
+            .to_html(&[], &prefill, crate::layout::Mode::Static)
+            .into_string();
+        assert!(html.contains(r#"name="issue-form[version]" placeholder="" value="1.2""#));
+        assert!(html.contains(r#"value="macOS" checked"#));
+        assert!(html.contains("<dd>bug, triage</dd>"));
+
+        assert_eq!(
+            messages(&form, &prefill),
+            vec!["query parameter `verison` does not match the id of any element"]
+        );
+    }
+
+    #[test]
+    fn repeated_parameters() {
+        let form = form();
+        // Later parameters win, so only the last dropdown value is checked against the options.
+        let prefill = Prefill::from_query(vec![
+            ("os".to_string(), "Windows".to_string()),
+            ("os".to_string(), "macOS".to_string()),
+            ("version".to_string(), "1.2".to_string()),
+            ("version".to_string(), "1.3".to_string()),
+        ]);
+        assert_eq!(prefill.field("os"), Some("macOS"));
+        assert_eq!(prefill.field("version"), Some("1.3"));
+        assert_eq!(messages(&form, &prefill), Vec::<String>::new());
+
+        let prefill = Prefill::from_query(vec![
+            ("os".to_string(), "macOS".to_string()),
+            ("os".to_string(), "Windows".to_string()),
+        ]);
+        assert_eq!(
+            messages(&form, &prefill),
+            vec!["`Windows` is not an option of dropdown `os`"]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::yaml::Schema::{self, Any, Mapping, Sequence, Tagged, Text};
+
+// cf. https://docs.github.com/en/communities/using-templates-to-encourage-useful-issues-and-pull-requests/syntax-for-githubs-form-schema
+/// Keys the issue form structs deserialize and which of them take strings,
+/// which must be kept in sync with the structs.
+pub(super) const FORM: Schema = Mapping(&[
+    ("name", Text),
+    ("description", Text),
+    ("title", Text),
+    ("labels", Any),
+    ("assignees", Any),
+    ("type", Text),
+    ("projects", Any),
+    ("body", Sequence(&BODY)),
+]);
+
+// cf. https://docs.github.com/en/discussions/managing-discussions-for-your-community/syntax-for-discussion-category-forms
+/// Keys the discussion form struct deserializes, sharing the body with issue forms.
+pub(super) const DISCUSSION: Schema =
+    Mapping(&[("title", Text), ("labels", Any), ("body", Sequence(&BODY))]);
+
+const BODY: Schema = Tagged(
+    "type",
+    &[
+        (
+            "checkboxes",
+            Mapping(&[
+                ("type", Any),
+                ("id", Text),
+                (
+                    "attributes",
+                    Mapping(&[
+                        ("label", Text),
+                        ("description", Text),
+                        (
+                            "options",
+                            Sequence(&Mapping(&[("label", Text), ("required", Any)])),
+                        ),
+                    ]),
+                ),
+                // Not GitHub's, but reported as misplaced rather than unknown.
+                ("validations", Any),
+            ]),
+        ),
+        (
+            "dropdown",
+            Mapping(&[
+                ("type", Any),
+                ("id", Text),
+                (
+                    "attributes",
+                    Mapping(&[
+                        ("label", Text),
+                        ("description", Text),
+                        ("multiple", Any),
+                        ("options", Sequence(&Text)),
+                        ("default", Any),
+                    ]),
+                ),
+                ("validations", VALIDATIONS),
+            ]),
+        ),
+        (
+            "input",
+            Mapping(&[
+                ("type", Any),
+                ("id", Text),
+                (
+                    "attributes",
+                    Mapping(&[
+                        ("label", Text),
+                        ("description", Text),
+                        ("placeholder", Text),
+                        ("value", Text),
+                    ]),
+                ),
+                ("validations", VALIDATIONS),
+            ]),
+        ),
+        (
+            "markdown",
+            Mapping(&[
+                ("type", Any),
+                ("attributes", Mapping(&[("value", Text)])),
+                // Likewise reported as misplaced.
+                ("validations", Any),
+            ]),
+        ),
+        (
+            "textarea",
+            Mapping(&[
+                ("type", Any),
+                ("id", Text),
+                (
+                    "attributes",
+                    Mapping(&[
+                        ("label", Text),
+                        ("description", Text),
+                        ("placeholder", Text),
+                        ("value", Text),
+                        ("render", Text),
+                    ]),
+                ),
+                ("validations", VALIDATIONS),
+            ]),
+        ),
+    ],
+);
+
+const VALIDATIONS: Schema = Mapping(&[("required", Any)]);
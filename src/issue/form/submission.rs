@@ -0,0 +1,404 @@
+use std::collections::{BTreeMap, HashMap};
+
+use maud::{html, Markup};
+use serde::Serialize;
+
+use super::{anchors, BodyType, Form, Markdown};
+use crate::layout::{self, Mode};
+
+/// Values entered into a form, keyed by element anchor.
+/// Checkboxes answer with the indices of the checked options.
+pub type Answers = HashMap<String, Vec<String>>;
+
+/// Answer to a single element recovered from an issue body.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    /// Inputs, textareas and single-select dropdowns; `None` stands for `_No response_`.
+    Text(Option<String>),
+    /// Multi-select dropdowns and the checked options of checkboxes.
+    Selection(Vec<String>),
+}
+
+const NO_RESPONSE: &str = "_No response_";
+
+/// Collects `issue-form[<id>]` fields posted from the preview page.
+pub fn answers(fields: Vec<(String, String)>) -> Answers {
+    let mut answers = Answers::new();
+    for (name, value) in fields {
+        if let Some(id) = name
+            .strip_prefix("issue-form[")
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            answers.entry(id.to_string()).or_default().push(value);
+        }
+    }
+    answers
+}
+
+impl Form {
+    /// Builds the markdown GitHub writes into the issue created from `answers`.
+    pub fn issue_body(&self, answers: &Answers) -> String {
+        self.body
+            .iter()
+            .zip(anchors(&self.body))
+            .filter_map(|(item, anchor)| {
+                let label = item.label()?;
+                let values = anchor
+                    .and_then(|key| answers.get(&key))
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                Some(format!("### {label}\n\n{}", response(item, values)))
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Recovers the answers from an issue body written by `issue_body` or GitHub.
+    /// Only `### <label>` headings of this form start a section, and never inside code fences,
+    /// so that answers may contain headings of their own.
+    pub fn parse_issue_body(&self, body: &str) -> BTreeMap<String, Answer> {
+        let mut sections: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut current = None;
+        let mut fence: Option<&str> = None;
+        for line in body.lines() {
+            let trimmed = line.trim_start();
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+            } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                // The closing fence must be at least as long as the opening one.
+                let marker = trimmed.chars().next().unwrap_or_default();
+                let length = trimmed.len() - trimmed.trim_start_matches(marker).len();
+                fence = Some(&trimmed[..length]);
+            } else if let Some(label) = line.strip_prefix("### ").map(str::trim) {
+                if !sections.contains_key(label)
+                    && self.body.iter().any(|item| item.label() == Some(label))
+                {
+                    sections.insert(label, vec![]);
+                    current = Some(label);
+                    continue;
+                }
+            }
+            if let Some(label) = current {
+                sections.entry(label).or_default().push(line);
+            }
+        }
+
+        self.body
+            .iter()
+            .zip(anchors(&self.body))
+            .filter_map(|(item, anchor)| {
+                let key = anchor?;
+                let content = sections
+                    .get(item.label()?)
+                    .map(|lines| lines.join("\n").trim().to_string())
+                    .filter(|content| content != NO_RESPONSE && !content.is_empty());
+                Some((key, answer(item, content)))
+            })
+            .collect()
+    }
+
+    pub fn to_submission_html(&self, answers: &Answers, mode: Mode) -> Markup {
+        let body = self.issue_body(answers);
+        layout::page(
+            mode,
+            html! {
+                h2 {"Issue body"}
+                pre."issue-body" {(body)}
+                h2 {"Preview"}
+                div."issue-preview" {(Markdown(body))}
+                a href="" {"Back to the form"}
+            },
+        )
+    }
+}
+
+fn response(item: &BodyType, values: &[String]) -> String {
+    let filled = values
+        .iter()
+        .filter(|value| !value.trim().is_empty())
+        .collect::<Vec<_>>();
+    match item {
+        BodyType::Checkboxes { attributes, .. } => attributes
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, option)| {
+                let checked = values.contains(&index.to_string());
+                format!("- [{}] {}", if checked { "X" } else { " " }, option.label.0)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ if filled.is_empty() => NO_RESPONSE.to_string(),
+        BodyType::Dropdown { attributes, .. } => attributes
+            .options
+            .iter()
+            .filter(|option| values.contains(option))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", "),
+        BodyType::Textarea { attributes, .. } => match &attributes.render {
+            Some(language) => {
+                let fence = fence(filled[0]);
+                format!("{fence}{language}\n{}\n{fence}", filled[0])
+            }
+            None => filled[0].clone(),
+        },
+        _ => filled[0].clone(),
+    }
+}
+
+fn answer(item: &BodyType, content: Option<String>) -> Answer {
+    match item {
+        BodyType::Checkboxes { .. } => Answer::Selection(
+            content
+                .iter()
+                .flat_map(|content| content.lines())
+                .filter_map(|line| {
+                    line.trim()
+                        .strip_prefix("- [X] ")
+                        .or_else(|| line.trim().strip_prefix("- [x] "))
+                })
+                .map(str::to_string)
+                .collect(),
+        ),
+        BodyType::Dropdown { attributes, .. } if attributes.multiple => {
+            Answer::Selection(content.map_or_else(Vec::new, |content| {
+                split_options(&content, &attributes.options)
+            }))
+        }
+        BodyType::Textarea { attributes, .. } if attributes.render.is_some() => {
+            Answer::Text(content.map(|content| unfence(&content)))
+        }
+        _ => Answer::Text(content),
+    }
+}
+
+/// Splits comma-joined selections, keeping options that contain commas themselves in one piece.
+fn split_options(content: &str, options: &[String]) -> Vec<String> {
+    let mut selections = vec![];
+    let mut buffer = String::new();
+    for token in content.split(", ") {
+        if !buffer.is_empty() {
+            buffer.push_str(", ");
+        }
+        buffer.push_str(token);
+        if options.contains(&buffer) {
+            selections.push(std::mem::take(&mut buffer));
+        }
+    }
+    if !buffer.is_empty() {
+        selections.push(buffer);
+    }
+    selections
+}
+
+/// Backticks enough to fence `content`, i.e. more than any run of them inside it.
+fn fence(content: &str) -> String {
+    let longest = content
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn unfence(content: &str) -> String {
+    let mut lines = content.lines().collect::<Vec<_>>();
+    let closing = lines.last().map(|line| line.trim()).unwrap_or_default();
+    if lines.len() >= 2
+        && closing.len() >= 3
+        && closing.chars().all(|c| c == '`')
+        && lines[0].starts_with(closing)
+    {
+        lines.remove(0);
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn form() -> Form {
+        serde_yaml::from_str(
+            r#"
+name: Bug
+description: Report a bug
+body:
+  - type: markdown
+    attributes:
+      value: Thanks!
+  - type: input
+    id: version
+    attributes:
+      label: Version
+  - type: dropdown
+    id: browsers
+    attributes:
+      label: Browsers
+      multiple: true
+      options: [Firefox, Chrome, Safari]
+  - type: textarea
+    id: logs
+    attributes:
+      label: Logs
+      render: shell
+  - type: textarea
+    id: notes
+    attributes:
+      label: Notes
+  - type: checkboxes
+    id: terms
+    attributes:
+      label: Code of Conduct
+      options:
+        - label: I agree
+        - label: I **really** agree
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn issue_body() {
+        let form = form();
+        let answers = answers(vec![
+            ("issue-form[version]".to_string(), "1.2.3".to_string()),
+            ("issue-form[browsers]".to_string(), "Safari".to_string()),
+            ("issue-form[browsers]".to_string(), "Firefox".to_string()),
+            ("issue-form[logs]".to_string(), "$ gh form".to_string()),
+            ("issue-form[notes]".to_string(), " ".to_string()),
+            ("issue-form[terms]".to_string(), "1".to_string()),
+        ]);
+        assert_eq!(
+            form.issue_body(&answers),
+            r#"### Version
+
+1.2.3
+
+### Browsers
+
+Firefox, Safari
+
+### Logs
+
+```shell
+$ gh form
+```
+
+### Notes
+
+_No response_
+
+### Code of Conduct
+
+- [ ] I agree
+- [X] I **really** agree"#
+        );
+    }
+
+    #[test]
+    fn parse_issue_body() {
+        let body = r#"### Version
+
+1.2.3
+
+### Browsers
+
+Firefox, Safari
+
+### Logs
+
+```shell
+### Not a heading
+$ gh form
+```
+
+### Notes
+
+_No response_
+
+### Code of Conduct
+
+- [ ] I agree
+- [X] I **really** agree"#;
+        let answers = form().parse_issue_body(body);
+        assert_eq!(
+            serde_json::to_value(&answers).unwrap(),
+            serde_json::json!({
+                "version": "1.2.3",
+                "browsers": ["Firefox", "Safari"],
+                "logs": "### Not a heading\n$ gh form",
+                "notes": null,
+                "terms": ["I **really** agree"],
+            })
+        );
+    }
+
+    #[test]
+    fn colliding_anchors() {
+        let form: Form = serde_yaml::from_str(
+            r#"
+name: Bug
+description: Report a bug
+body:
+  - type: input
+    id: version
+    attributes:
+      label: App version
+  - type: input
+    attributes:
+      label: Version
+  - type: input
+    attributes:
+      label: "???"
+"#,
+        )
+        .unwrap();
+        let answers = answers(vec![
+            ("issue-form[version]".to_string(), "1.2.3".to_string()),
+            ("issue-form[version-2]".to_string(), "4.5.6".to_string()),
+            ("issue-form[field-2]".to_string(), "Why".to_string()),
+        ]);
+        let body = form.issue_body(&answers);
+        assert_eq!(
+            body,
+            "### App version\n\n1.2.3\n\n### Version\n\n4.5.6\n\n### ???\n\nWhy"
+        );
+        assert_eq!(
+            serde_json::to_value(form.parse_issue_body(&body)).unwrap(),
+            serde_json::json!({
+                "version": "1.2.3",
+                "version-2": "4.5.6",
+                "field-2": "Why",
+            })
+        );
+    }
+
+    #[test]
+    fn nested_fence() {
+        let form = form();
+        let logs = "```\n$ gh form\n```";
+        let answers = answers(vec![("issue-form[logs]".to_string(), logs.to_string())]);
+        let body = form.issue_body(&answers);
+        assert!(body.contains("````shell\n```\n$ gh form\n```\n````"));
+        assert_eq!(
+            form.parse_issue_body(&body)["logs"],
+            Answer::Text(Some(logs.to_string()))
+        );
+    }
+
+    #[test]
+    fn split_options_with_commas() {
+        let options = vec!["Yes, always".to_string(), "No".to_string()];
+        assert_eq!(
+            split_options("Yes, always, No", &options),
+            vec!["Yes, always".to_string(), "No".to_string()]
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+
+use super::{language, BodyType, Form, SequenceLike};
+use crate::diagnostic::{Diagnostic, Rule};
+
+// cf. https://docs.github.com/en/communities/using-templates-to-encourage-useful-issues-and-pull-requests/common-validation-errors-when-creating-issue-forms
+impl Form {
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = validate_body(&self.body);
+        for (index, project) in self.projects.items().iter().enumerate() {
+            if !is_project(project) {
+                // A comma-delimited string has no node per project to point at.
+                let path = match &self.projects {
+                    SequenceLike::Sequence(_) => format!("projects[{index}]"),
+                    SequenceLike::CommaDelimited(_) => "projects".to_string(),
+                };
+                diagnostics.push(Diagnostic::new(
+                    Rule::InvalidProject,
+                    path,
+                    format!("project `{project}` must be in the form `owner/number`"),
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+/// Whether `project` names a project as `owner/number`, e.g. `octo-org/1`.
+fn is_project(project: &str) -> bool {
+    match project.split_once('/') {
+        Some((owner, number)) => {
+            !owner.is_empty()
+                && owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// Validates the elements of a form, which issue and discussion forms share.
+pub(super) fn validate_body(body: &[BodyType]) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    if body
+        .iter()
+        .all(|item| matches!(item, BodyType::Markdown { .. }))
+    {
+        diagnostics.push(Diagnostic::new(
+            Rule::MarkdownOnlyBody,
+            "body",
+            "body must contain at least one non-markdown field",
+        ));
+    }
+
+    let mut ids = HashMap::new();
+    let mut labels = HashMap::new();
+    for (index, item) in body.iter().enumerate() {
+        if let Some(id) = item.id() {
+            if !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                diagnostics.push(Diagnostic::new(
+                    Rule::InvalidId,
+                    format!("body[{index}].id"),
+                    format!("id `{id}` can only contain numbers, letters, -, _"),
+                ));
+            }
+            if let Some(first) = ids.get(id) {
+                diagnostics.push(Diagnostic::new(
+                    Rule::DuplicateId,
+                    format!("body[{index}].id"),
+                    format!("id `{id}` is already used by body[{first}]"),
+                ));
+            } else {
+                ids.insert(id, index);
+            }
+        }
+        if let Some(label) = item.label() {
+            if let Some(first) = labels.get(label) {
+                diagnostics.push(Diagnostic::new(
+                    Rule::DuplicateLabel,
+                    format!("body[{index}].attributes.label"),
+                    format!("label `{label}` is already used by body[{first}]"),
+                ));
+            } else {
+                labels.insert(label, index);
+            }
+        }
+        match item {
+            BodyType::Checkboxes {
+                validations: Some(_),
+                ..
+            } => diagnostics.push(Diagnostic::new(
+                Rule::MisplacedValidations,
+                format!("body[{index}].validations"),
+                "checkboxes take no validations, set `required` on each option instead",
+            )),
+            BodyType::Markdown {
+                validations: Some(_),
+                ..
+            } => diagnostics.push(Diagnostic::new(
+                Rule::MisplacedValidations,
+                format!("body[{index}].validations"),
+                "markdown takes no validations as it has no answer",
+            )),
+            BodyType::Textarea { attributes, .. } => {
+                if let Some(render) = &attributes.render {
+                    if !language::is_known(render) {
+                        let mut message =
+                            format!("`{render}` is not a language GitHub can format code as");
+                        if let Some(suggestion) = language::suggest(render) {
+                            message.push_str(&format!(", did you mean `{suggestion}`?"));
+                        }
+                        diagnostics.push(Diagnostic::new(
+                            Rule::UnknownLanguage,
+                            format!("body[{index}].attributes.render"),
+                            message,
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let BodyType::Dropdown { attributes, .. } = item {
+            validate_options(index, &attributes.options, &mut diagnostics);
+            if let Some(default) = attributes.default {
+                if default >= attributes.options.len() {
+                    diagnostics.push(Diagnostic::new(
+                        Rule::DefaultOutOfBounds,
+                        format!("body[{index}].attributes.default"),
+                        format!(
+                            "default `{default}` is out of bounds for {} options",
+                            attributes.options.len()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn validate_options(index: usize, options: &[String], diagnostics: &mut Vec<Diagnostic>) {
+    if options.is_empty() {
+        diagnostics.push(Diagnostic::new(
+            Rule::EmptyOptions,
+            format!("body[{index}].attributes.options"),
+            "options must contain at least one item",
+        ));
+    }
+    for (position, option) in options.iter().enumerate() {
+        let path = format!("body[{index}].attributes.options[{position}]");
+        if option.trim().eq_ignore_ascii_case("none") {
+            diagnostics.push(Diagnostic::new(
+                Rule::ReservedOption,
+                path.clone(),
+                format!("option `{option}` is reserved by GitHub"),
+            ));
+        }
+        if options[..position].contains(option) {
+            diagnostics.push(Diagnostic::new(
+                Rule::DuplicateOption,
+                path,
+                format!("option `{option}` is duplicated"),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn rules(yaml: &str) -> Vec<(&'static str, String)> {
+        let form: Form = serde_yaml::from_str(yaml).unwrap();
+        form.validate()
+            .into_iter()
+            .map(|diagnostic| (diagnostic.rule.name(), diagnostic.path))
+            .collect()
+    }
+
+    #[test]
+    fn valid_form() {
+        let yaml = r#"
+name: Bug
+description: Report a bug
+body:
+  - type: markdown
+    attributes:
+      value: Thanks!
+  - type: input
+    id: version_number-1
+    attributes:
+      label: Version
+"#;
+        assert_eq!(rules(yaml), vec![]);
+    }
+
+    #[test]
+    fn markdown_only_body() {
+        let yaml = r#"
+name: Bug
+description: Report a bug
+body:
+  - type: markdown
+    attributes:
+      value: Thanks!
+"#;
+        assert_eq!(
+            rules(yaml),
+            vec![("markdown-only-body", "body".to_string())]
+        );
+    }
+
+    #[test]
+    fn ids_and_labels() {
+        let yaml = r#"
+name: Bug
+description: Report a bug
+body:
+  - type: input
+    id: version
+    attributes:
+      label: Version
+  - type: textarea
+    id: version
+    attributes:
+      label: Version
+  - type: input
+    id: os name
+    attributes:
+      label: OS
+"#;
+        assert_eq!(
+            rules(yaml),
+            vec![
+                ("duplicate-id", "body[1].id".to_string()),
+                ("duplicate-label", "body[1].attributes.label".to_string()),
+                ("invalid-id", "body[2].id".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn misplaced_validations() {
+        let yaml = r#"
+name: Bug
+description: Report a bug
+body:
+  - type: markdown
+    attributes:
+      value: Thanks!
+    validations:
+      required: true
+  - type: checkboxes
+    attributes:
+      label: Terms
+      options:
+        - label: I agree
+          required: true
+    validations:
+      required: true
+  - type: input
+    attributes:
+      label: Version
+    validations:
+      required: true
+"#;
+        assert_eq!(
+            rules(yaml),
+            vec![
+                ("misplaced-validations", "body[0].validations".to_string()),
+                ("misplaced-validations", "body[1].validations".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_language() {
+        let yaml = r#"
+name: Bug
+description: Report a bug
+body:
+  - type: textarea
+    attributes:
+      label: Logs
+      render: shel
+  - type: textarea
+    attributes:
+      label: Config
+      render: Emacs-Lisp
+  - type: textarea
+    attributes:
+      label: Script
+      render: js
+  - type: textarea
+    attributes:
+      label: Llvm
+      render: llvm
+  - type: textarea
+    attributes:
+      label: Typst
+      render: typst
+  - type: textarea
+    attributes:
+      label: Roff
+      render: roff
+  - type: textarea
+    attributes:
+      label: Nushell
+      render: nushell
+  - type: textarea
+    attributes:
+      label: Hack
+      render: hack
+  - type: textarea
+    attributes:
+      label: Svg
+      render: svg
+"#;
+        let form: Form = serde_yaml::from_str(yaml).unwrap();
+        let messages = form
+            .validate()
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec!["`shel` is not a language GitHub can format code as, did you mean `shell`?"]
+        );
+    }
+
+    #[test]
+    fn projects() {
+        let yaml = r#"
+name: Bug
+description: Report a bug
+type: Bug
+projects: [octo-org/1, octo-org, octo-org/one, /2]
+body:
+  - type: input
+    attributes:
+      label: Version
+"#;
+        assert_eq!(
+            rules(yaml),
+            vec![
+                ("invalid-project", "projects[1]".to_string()),
+                ("invalid-project", "projects[2]".to_string()),
+                ("invalid-project", "projects[3]".to_string()),
+            ]
+        );
+        let yaml = r#"
+name: Bug
+description: Report a bug
+projects: octo-org/1, octo-org
+body:
+  - type: input
+    attributes:
+      label: Version
+"#;
+        assert_eq!(
+            rules(yaml),
+            vec![("invalid-project", "projects".to_string())]
+        );
+    }
+
+    #[test]
+    fn dropdown_options() {
+        let yaml = r#"
+name: Bug
+description: Report a bug
+body:
+  - type: dropdown
+    id: browser
+    attributes:
+      label: Browser
+      options: [Firefox, None, Firefox]
+      default: 2
+  - type: dropdown
+    id: os
+    attributes:
+      label: OS
+      options: []
+      default: 0
+"#;
+        assert_eq!(
+            rules(yaml),
+            vec![
+                (
+                    "reserved-option",
+                    "body[0].attributes.options[1]".to_string()
+                ),
+                (
+                    "duplicate-option",
+                    "body[0].attributes.options[2]".to_string()
+                ),
+                ("empty-options", "body[1].attributes.options".to_string()),
+                (
+                    "default-out-of-bounds",
+                    "body[1].attributes.default".to_string()
+                ),
+            ]
+        );
+    }
+}
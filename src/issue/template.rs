@@ -0,0 +1,196 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use maud::{html, Markup};
+use serde::Deserialize;
+
+use super::form::{label_chips, metadata, Markdown, SequenceLike};
+use crate::{
+    diagnostic::{self, Diagnostic, Location, Rule},
+    layout::{self, Mode},
+    yaml::{Document, Schema},
+};
+
+/// Whether `file_name` is a Markdown issue template rather than a form.
+pub fn is_markdown(file_name: &str) -> bool {
+    file_name.ends_with(".md")
+}
+
+/// Whether the Markdown file at `file` starts with front matter, which makes it a template.
+pub fn has_front_matter(file: impl AsRef<Path>) -> bool {
+    fs::read_to_string(file).is_ok_and(|source| split_front_matter(&source).is_some())
+}
+
+/// Reads the Markdown issue template at `file` and validates its front matter.
+/// Keys the front matter does not know of are reported as well if `strict` is set.
+pub fn load(file: impl AsRef<Path>, strict: bool) -> Result<(Option<Template>, Vec<Diagnostic>)> {
+    let file = file.as_ref();
+    let source =
+        fs::read_to_string(file).with_context(|| format!("Failed to open {}", file.display()))?;
+    let (front_matter, body) = match split_front_matter(&source) {
+        Some(split) => split,
+        None => {
+            let diagnostic = Diagnostic {
+                location: Some(Location {
+                    file: file.display().to_string(),
+                    line: 1,
+                    column: 1,
+                    width: 1,
+                    snippet: source.lines().next().unwrap_or_default().to_string(),
+                }),
+                ..Diagnostic::new(
+                    Rule::MissingFrontMatter,
+                    "",
+                    "template has no front matter, so GitHub does not list it in the chooser",
+                )
+            };
+            return Ok((None, vec![diagnostic]));
+        }
+    };
+    // The front matter keeps its opening `---`, which YAML reads as the start of a document,
+    // so that lines in diagnostics match the template.
+    let document = Document::new(file.display().to_string(), front_matter);
+    let (front_matter, mut diagnostics) = document.load(FrontMatter::validate);
+    diagnostics.splice(0..0, document.lint(&SCHEMA, strict));
+    let template = front_matter.map(|front_matter| Template {
+        front_matter,
+        body: body.to_string(),
+    });
+    Ok((template, diagnostics))
+}
+
+/// Splits a template into the front matter including its opening `---`, and the body.
+fn split_front_matter(source: &str) -> Option<(&str, &str)> {
+    let mut lines = source.split_inclusive('\n');
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+    let mut offset = source.find('\n')? + 1;
+    for line in lines {
+        if line.trim_end() == "---" {
+            return Some((&source[..offset], &source[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Keys `FrontMatter` deserializes and which of them take strings.
+const SCHEMA: Schema = Schema::Mapping(&[
+    ("name", Schema::Text),
+    ("about", Schema::Text),
+    ("title", Schema::Text),
+    ("labels", Schema::Any),
+    ("assignees", Schema::Any),
+]);
+
+// cf. https://docs.github.com/en/communities/using-templates-to-encourage-useful-issues-and-pull-requests/manually-creating-a-single-issue-template-for-your-repository
+#[derive(Debug, Deserialize)]
+struct FrontMatter {
+    name: String,
+    about: String,
+    title: Option<String>,
+    #[serde(default)]
+    labels: SequenceLike,
+    #[serde(default)]
+    assignees: SequenceLike,
+}
+
+impl FrontMatter {
+    fn validate(&self) -> Vec<Diagnostic> {
+        [("name", &self.name), ("about", &self.about)]
+            .into_iter()
+            .filter(|(_, value)| value.trim().is_empty())
+            .map(|(key, _)| {
+                Diagnostic::new(
+                    Rule::EmptyFrontMatterField,
+                    key,
+                    format!("{key} must not be empty for GitHub to list the template"),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Markdown issue template, which prefills the issue body instead of asking questions.
+#[derive(Debug)]
+pub struct Template {
+    front_matter: FrontMatter,
+    body: String,
+}
+
+impl Template {
+    /// Renders the editor GitHub shows for the template, with the body rendered below it.
+    pub fn to_html(&self, diagnostics: &[Diagnostic], mode: Mode) -> Markup {
+        let front_matter = &self.front_matter;
+        layout::page(
+            mode,
+            html! {
+                (diagnostic::render_all(diagnostics))
+                (metadata(&front_matter.name, &front_matter.about, &front_matter.labels, &front_matter.assignees, &[]))
+                div."issue-title" {
+                    label {
+                        h3 required="required" {"Title"}
+                    }
+                    input."form-input" type="text" name="issue-title" placeholder="Title" value=[&front_matter.title];
+                }
+                label {
+                    h3 {"Add a description"}
+                }
+                textarea."form-textarea" name="issue-body" {(self.body)}
+                h2 {"Preview"}
+                div."issue-preview" {(Markdown(self.body.clone()))}
+            },
+        )
+    }
+
+    pub fn summarize(&self, link: &str) -> Markup {
+        let front_matter = &self.front_matter;
+        html! {
+            div.summary {
+                div {
+                    strong.name {(front_matter.name)}
+                    div.description {(front_matter.about)}
+                    (label_chips(&front_matter.labels.items()))
+                    @if let Some(title) = &front_matter.title {
+                        div.title {"Title: " code {(title)}}
+                    }
+                }
+                a.button href=(link) {"Preview"}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn front_matter() {
+        let directory =
+            std::env::temp_dir().join(format!("gh-form-template-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let file = directory.join("bug.md");
+        fs::write(
+            &file,
+            "---\nname: Bug report\nabout: ''\nlabels: bug, triage\n---\n\n## Steps\n",
+        )
+        .unwrap();
+        let (template, diagnostics) = load(&file, true).unwrap();
+        let template = template.unwrap();
+        assert_eq!(template.body, "\n## Steps\n");
+        assert_eq!(template.front_matter.labels.items(), vec!["bug", "triage"]);
+        let diagnostics = diagnostics
+            .into_iter()
+            .map(|diagnostic| (diagnostic.rule.name(), diagnostic.location.unwrap().line))
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostics, vec![("empty-front-matter-field", 3)]);
+
+        fs::write(&file, "## Steps\n").unwrap();
+        let (template, diagnostics) = load(&file, true).unwrap();
+        assert!(template.is_none());
+        assert_eq!(diagnostics[0].rule.name(), "missing-front-matter");
+    }
+}
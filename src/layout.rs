@@ -0,0 +1,90 @@
+use maud::{html, Markup, PreEscaped, DOCTYPE};
+use rsass::{
+    compile_scss,
+    output::{Format, Style},
+};
+use rust_embed::RustEmbed;
+
+use crate::diagnostic::{self, Diagnostic};
+
+/// Reloads the page whenever the server reports a change to the templates.
+const LIVE_RELOAD: &str = r#"new EventSource("events").onmessage = () => location.reload();"#;
+
+/// Where rendered pages are going to be served from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The preview server, which reloads pages on changes.
+    Server,
+    /// Plain files written by the `build` subcommand.
+    Static,
+}
+
+impl Mode {
+    /// Relative link to the page `name`, e.g. the preview of the template `bug.yml`.
+    pub fn link(self, yaml: &str) -> String {
+        match self {
+            Self::Server => yaml.to_string(),
+            Self::Static => format!("{yaml}.html"),
+        }
+    }
+}
+
+/// Wraps `body` in the document every preview page shares.
+/// Links are relative so that the pages work from any directory.
+pub fn page(mode: Mode, body: Markup) -> Markup {
+    html! {
+        (DOCTYPE)
+        html lang="en" {
+            head {
+                meta charset="UTF-8";
+                title {"Issue Form Previewer"}
+            }
+            link
+                rel="stylesheet"
+                type="text/css"
+                href="https://cdnjs.cloudflare.com/ajax/libs/github-markdown-css/5.1.0/github-markdown.min.css";
+            link
+                rel="stylesheet"
+                type="text/css"
+                href="assets/extra.css";
+            body ."markdown-body" {
+                (body)
+                @if mode == Mode::Server {
+                    script {(PreEscaped(LIVE_RELOAD))}
+                }
+            }
+        }
+    }
+}
+
+pub fn failure_page(yaml: &str, diagnostics: &[Diagnostic], mode: Mode) -> Markup {
+    page(
+        mode,
+        html! {
+            h3 {(format!("Failed to deserialize {yaml}"))}
+            (diagnostic::render_all(diagnostics))
+        },
+    )
+}
+
+#[derive(RustEmbed)]
+#[folder = "src/assets/"]
+struct Asset;
+
+/// Compiles the embedded stylesheet at `path`, e.g. `extra.scss`.
+pub fn stylesheet(path: &str) -> Option<String> {
+    let content = Asset::get(path)?;
+    let compiled_css = String::from_utf8(
+        compile_scss(
+            &content.data,
+            Format {
+                style: Style::Compressed,
+                precision: 5,
+            },
+        )
+        .expect("Stylesheet is embedded at compile-time, so this should never fail.")
+        .to_vec(),
+    )
+    .expect("Stylesheet is embedded at compile-time, so this should never fail.");
+    Some(compiled_css)
+}
@@ -1,13 +1,22 @@
+mod build;
+mod check;
 mod commands;
+mod diagnostic;
 mod handlers;
 mod issue;
+mod layout;
+mod pull_request;
+mod report;
+mod yaml;
 
-use std::{net::SocketAddr, sync::Arc};
+use std::{fs, net::SocketAddr, sync::Arc};
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use axum::{routing::get, Extension, Router};
 use clap::Parser;
-use tracing::info;
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -15,11 +24,52 @@ async fn main() -> Result<()> {
     let args = commands::Args::parse();
 
     match args.action {
-        commands::Action::Preview { directory, port } => {
-            let state = Arc::new(handlers::AppState { directory });
+        commands::Action::Preview {
+            directory,
+            port,
+            strict,
+            discussion,
+        } => {
+            let (changes, _) = broadcast::channel(16);
+            let sender = changes.clone();
+            let mut watcher =
+                notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                    match res {
+                        Ok(event) if !event.kind.is_access() => {
+                            // Nobody may be subscribed yet, which is not an error.
+                            let _ = sender.send(());
+                        }
+                        Ok(_) => {}
+                        Err(err) => warn!("Failed to watch templates: {}", err),
+                    }
+                })?;
+            watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+            // The security policy and pull request templates live outside `directory`.
+            for other in issue::community_directories(&directory)
+                .into_iter()
+                .chain(pull_request::template_directories(&directory))
+                .filter(|other| other.is_dir())
+            {
+                if let Err(err) = watcher.watch(&other, RecursiveMode::NonRecursive) {
+                    warn!("Failed to watch {}: {}", other.display(), err);
+                }
+            }
+
+            let state = Arc::new(handlers::AppState {
+                directory,
+                changes,
+                strict,
+                discussion,
+            });
             let app = Router::new()
                 .route("/", get(handlers::top_page))
-                .route("/:yaml", get(handlers::preview))
+                .route("/events", get(handlers::events))
+                .route("/link", get(handlers::link))
+                .route("/blank", get(handlers::blank_issue))
+                .route("/security", get(handlers::security_policy))
+                .route("/pull-requests", get(handlers::pull_requests))
+                .route("/pull-request", get(handlers::pull_request))
+                .route("/:yaml", get(handlers::preview).post(handlers::submit))
                 .route("/assets/*file", get(handlers::serve_static))
                 .layer(Extension(state));
 
@@ -30,6 +80,69 @@ async fn main() -> Result<()> {
                 .await
                 .unwrap();
         }
+        commands::Action::Build { directory, out } => {
+            build::build(&directory, &out)?;
+        }
+        commands::Action::Parse { form, body } => {
+            let (form, diagnostics) = issue::form::load(&form, false)?;
+            let form =
+                form.ok_or_else(|| anyhow!(report::render(commands::Format::Human, &diagnostics)))?;
+            let body = fs::read_to_string(&body)
+                .with_context(|| format!("Failed to open {}", body.display()))?;
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&form.parse_issue_body(&body))?
+            );
+        }
+        commands::Action::Url {
+            form,
+            repo,
+            title,
+            set,
+        } => {
+            let template = form
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let (form, diagnostics) = issue::form::load(&form, false)?;
+            let form =
+                form.ok_or_else(|| anyhow!(report::render(commands::Format::Human, &diagnostics)))?;
+            let mut query = title
+                .map(|title| vec![("title".to_string(), title)])
+                .unwrap_or_default();
+            for pair in set {
+                let (id, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("Expected ID=VALUE, but got {}", pair))?;
+                query.push((id.to_string(), value.to_string()));
+            }
+            let prefill = issue::form::Prefill::from_query(query);
+            let diagnostics = form.validate_prefill(&prefill);
+            println!("{}", prefill.to_url(&repo, &template));
+            if !diagnostics.is_empty() {
+                eprint!("{}", report::render(commands::Format::Human, &diagnostics));
+                std::process::exit(1);
+            }
+        }
+        commands::Action::Check {
+            directory,
+            format,
+            no_strict,
+            discussion,
+        } => {
+            let diagnostics = if discussion {
+                check::check_discussions(&directory, !no_strict)?
+            } else {
+                check::check(&directory, !no_strict)?
+            };
+            print!("{}", report::render(format, &diagnostics));
+            if diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.severity() == diagnostic::Severity::Error)
+            {
+                std::process::exit(1);
+            }
+        }
     }
 
     Ok(())
@@ -0,0 +1,217 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use maud::{html, Markup};
+use percent_encoding::utf8_percent_encode;
+
+use crate::{
+    issue,
+    layout::{self, Mode},
+};
+
+const FILE_NAME: &str = "PULL_REQUEST_TEMPLATE.md";
+const DIRECTORY_NAME: &str = "PULL_REQUEST_TEMPLATE";
+
+/// Pull request template found in one of the places GitHub looks for them.
+// cf. https://docs.github.com/en/communities/using-templates-to-encourage-useful-issues-and-pull-requests/creating-a-pull-request-template-for-your-repository
+#[derive(Debug, PartialEq)]
+pub struct Template {
+    pub file: PathBuf,
+    /// File name to pass as `?template=`, if the template is in a `PULL_REQUEST_TEMPLATE` directory.
+    pub name: Option<String>,
+}
+
+impl Template {
+    /// Where the preview server shows the template.
+    fn link(&self) -> String {
+        match &self.name {
+            Some(name) => format!(
+                "pull-request?template={}",
+                utf8_percent_encode(name, issue::form::QUERY)
+            ),
+            None => "pull-request".to_string(),
+        }
+    }
+}
+
+/// Finds the pull request templates of the repository whose issue templates are in `directory`.
+/// The single-file template GitHub uses by default, if any, comes first.
+pub fn find(directory: &Path) -> Vec<Template> {
+    let mut default = None;
+    let mut templates = vec![];
+    for directory in issue::community_directories(directory) {
+        let entries = match fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+            let file_name = match path.file_name() {
+                Some(file_name) => file_name.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            if path.is_file() && file_name.eq_ignore_ascii_case(FILE_NAME) {
+                default.get_or_insert(Template {
+                    file: path,
+                    name: None,
+                });
+            } else if path.is_dir() && file_name.eq_ignore_ascii_case(DIRECTORY_NAME) {
+                for name in issue::list_templates(&path).unwrap_or_default() {
+                    if issue::template::is_markdown(&name)
+                        && !templates
+                            .iter()
+                            .any(|template: &Template| template.name.as_ref() == Some(&name))
+                    {
+                        templates.push(Template {
+                            file: path.join(&name),
+                            name: Some(name),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    templates.sort_by_key(|template| template.name.as_ref().map(|name| name.to_lowercase()));
+    default.into_iter().chain(templates).collect()
+}
+
+/// Lists the `PULL_REQUEST_TEMPLATE` directories in the places GitHub looks for templates.
+pub fn template_directories(directory: &Path) -> Vec<PathBuf> {
+    issue::community_directories(directory)
+        .iter()
+        .filter_map(|directory| fs::read_dir(directory).ok())
+        .flatten()
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(DIRECTORY_NAME))
+        })
+        .collect()
+}
+
+/// Renders the list of pull request templates with links to their previews.
+pub fn index(directory: &Path, mode: Mode) -> Markup {
+    let root = issue::repository_root(directory);
+    let templates = find(directory);
+    layout::page(
+        mode,
+        html! {
+            div."form-list-container" {
+                @if templates.is_empty() {
+                    p {"This repository has no pull request templates."}
+                }
+                @for template in &templates {
+                    div.summary {
+                        div {
+                            strong.name {
+                                @match &template.name {
+                                    Some(name) => (name),
+                                    None => "Default template",
+                                }
+                            }
+                            div.description {
+                                code {(template.file.strip_prefix(&root).unwrap_or(&template.file).display().to_string())}
+                            }
+                        }
+                        a.button href=(template.link()) {"Preview"}
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// Renders the pull request template chosen by `name` like GitHub's `?template=` does,
+/// or the default one if no name is given.
+/// Only templates GitHub would find are served, so `name` cannot point anywhere else.
+pub fn preview(directory: &Path, name: Option<&str>, mode: Mode) -> Result<Option<Markup>> {
+    let template = match find(directory)
+        .into_iter()
+        .find(|template| template.name.as_deref() == name)
+    {
+        Some(template) => template,
+        None => return Ok(None),
+    };
+    let body = fs::read_to_string(&template.file)
+        .with_context(|| format!("Failed to open {}", template.file.display()))?;
+    Ok(Some(layout::page(
+        mode,
+        html! {
+            div."issue-title" {
+                label {
+                    h3 required="required" {"Title"}
+                }
+                input."form-input" type="text" name="pull-request-title" placeholder="Title";
+            }
+            label {
+                h3 {"Add a description"}
+            }
+            textarea."form-textarea" name="pull-request-body" {(body)}
+            h2 {"Preview"}
+            div."issue-preview" {(issue::form::Markdown(body))}
+        },
+    )))
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn templates() {
+        let root =
+            std::env::temp_dir().join(format!("gh-form-pull-request-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let directory = root.join(".github/ISSUE_TEMPLATE");
+        fs::create_dir_all(&directory).unwrap();
+        fs::create_dir_all(root.join("docs/PULL_REQUEST_TEMPLATE")).unwrap();
+        fs::write(root.join("pull_request_template.md"), "## Summary\n").unwrap();
+        fs::write(root.join("docs/PULL_REQUEST_TEMPLATE/fix.md"), "Fixes #\n").unwrap();
+        fs::write(root.join("docs/PULL_REQUEST_TEMPLATE/Docs.md"), "").unwrap();
+        fs::write(root.join("docs/PULL_REQUEST_TEMPLATE/a & b #1.md"), "").unwrap();
+        fs::write(root.join("docs/PULL_REQUEST_TEMPLATE/notes.txt"), "").unwrap();
+
+        let names = find(&directory)
+            .into_iter()
+            .map(|template| template.name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                None,
+                Some("a & b #1.md".to_string()),
+                Some("Docs.md".to_string()),
+                Some("fix.md".to_string())
+            ]
+        );
+
+        let html = index(&directory, Mode::Server).into_string();
+        assert!(html.contains(
+            r#"<strong class="name">Default template</strong><div class="description"><code>pull_request_template.md</code></div>"#
+        ));
+        assert!(
+            html.contains(r#"<a class="button" href="pull-request?template=fix.md">Preview</a>"#)
+        );
+        assert!(html.contains(r#"href="pull-request?template=a%20%26%20b%20%231.md""#));
+
+        let html = preview(&directory, None, Mode::Server)
+            .unwrap()
+            .unwrap()
+            .into_string();
+        assert!(html.contains("Summary</h2>"));
+        let html = preview(&directory, Some("fix.md"), Mode::Server)
+            .unwrap()
+            .unwrap()
+            .into_string();
+        assert!(html.contains(">Fixes #\n</textarea>"));
+        assert!(preview(&directory, Some("../fix.md"), Mode::Server)
+            .unwrap()
+            .is_none());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
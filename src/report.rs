@@ -0,0 +1,249 @@
+use serde_json::{json, Value};
+
+use crate::{
+    commands::Format,
+    diagnostic::{Diagnostic, Location, Severity},
+};
+
+pub fn render(format: Format, diagnostics: &[Diagnostic]) -> String {
+    match format {
+        Format::Human => diagnostics
+            .iter()
+            .map(|diagnostic| format!("{diagnostic}\n\n"))
+            .collect(),
+        Format::Json => format!("{:#}\n", json(diagnostics)),
+        Format::Sarif => format!("{:#}\n", sarif(diagnostics)),
+        Format::Github => diagnostics.iter().map(workflow_command).collect(),
+    }
+}
+
+fn json(diagnostics: &[Diagnostic]) -> Value {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            json!({
+                "rule": diagnostic.rule.name(),
+                "severity": diagnostic.severity().to_string(),
+                "path": diagnostic.path,
+                "message": diagnostic.message,
+                "location": diagnostic.location.as_ref().map(|location| json!({
+                    "file": location.file,
+                    "line": location.line,
+                    "column": location.column,
+                })),
+                "related": diagnostic.related.as_ref().map(|related| json!({
+                    "file": related.location.file,
+                    "line": related.location.line,
+                    "column": related.location.column,
+                    "message": related.message,
+                })),
+            })
+        })
+        .collect()
+}
+
+// cf. https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+fn sarif(diagnostics: &[Diagnostic]) -> Value {
+    let mut rules = vec![];
+    for diagnostic in diagnostics {
+        if !rules.contains(&diagnostic.rule) {
+            rules.push(diagnostic.rule);
+        }
+    }
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules.iter().map(|rule| json!({
+                        "id": rule.name(),
+                        "defaultConfiguration": { "level": sarif_level(rule.severity()) },
+                    })).collect::<Vec<_>>(),
+                },
+            },
+            "results": diagnostics.iter().map(|diagnostic| {
+                let mut result = json!({
+                    "ruleId": diagnostic.rule.name(),
+                    "ruleIndex": rules.iter().position(|rule| rule == &diagnostic.rule),
+                    "level": sarif_level(diagnostic.severity()),
+                    "message": { "text": diagnostic.message },
+                });
+                if let Some(location) = &diagnostic.location {
+                    result["locations"] = json!([{ "physicalLocation": physical_location(location) }]);
+                }
+                if let Some(related) = &diagnostic.related {
+                    result["relatedLocations"] = json!([{
+                        "physicalLocation": physical_location(&related.location),
+                        "message": { "text": related.message },
+                    }]);
+                }
+                result
+            }).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+fn physical_location(location: &Location) -> Value {
+    json!({
+        "artifactLocation": { "uri": location.file.replace('\\', "/") },
+        "region": {
+            "startLine": location.line,
+            "startColumn": location.column,
+            "endColumn": end_column(location),
+        },
+    })
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+fn end_column(location: &Location) -> usize {
+    location.column + location.width
+}
+
+// cf. https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions#setting-an-error-message
+fn workflow_command(diagnostic: &Diagnostic) -> String {
+    let mut properties = vec![];
+    if let Some(location) = &diagnostic.location {
+        properties.push(format!("file={}", escape_property(&location.file)));
+        properties.push(format!("line={}", location.line));
+        properties.push(format!("col={}", location.column));
+        properties.push(format!("endColumn={}", end_column(location)));
+    }
+    properties.push(format!("title={}", escape_property(diagnostic.rule.name())));
+    let message = if diagnostic.path.is_empty() {
+        diagnostic.message.clone()
+    } else {
+        format!("{}: {}", diagnostic.path, diagnostic.message)
+    };
+    format!(
+        "::{} {}::{}\n",
+        diagnostic.severity(),
+        properties.join(","),
+        escape_data(&message)
+    )
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use crate::diagnostic::{Related, Rule};
+    use pretty_assertions::assert_eq;
+
+    fn diagnostics() -> Vec<Diagnostic> {
+        vec![Diagnostic {
+            location: Some(Location {
+                file: ".github/ISSUE_TEMPLATE/bug.yml".to_string(),
+                line: 5,
+                column: 9,
+                width: 3,
+                snippet: "    id: a,b".to_string(),
+            }),
+            ..Diagnostic::new(
+                Rule::InvalidId,
+                "body[0].id",
+                "id `a,b` can only contain numbers, letters, -, _",
+            )
+        }]
+    }
+
+    #[test]
+    fn github() {
+        assert_eq!(
+            render(Format::Github, &diagnostics()),
+            "::error file=.github/ISSUE_TEMPLATE/bug.yml,line=5,col=9,endColumn=12,title=invalid-id::body[0].id: id `a,b` can only contain numbers, letters, -, _\n"
+        );
+    }
+
+    #[test]
+    fn json_output() {
+        let mut diagnostics = diagnostics();
+        diagnostics.push(Diagnostic {
+            related: Some(Related {
+                message: "first defined here".to_string(),
+                location: Location {
+                    line: 3,
+                    column: 3,
+                    width: 5,
+                    snippet: "  label: OS".to_string(),
+                    ..diagnostics[0].location.clone().unwrap()
+                },
+            }),
+            ..Diagnostic::new(
+                Rule::DuplicateKey,
+                "attributes.label",
+                "key `label` is already defined at line 3, and only this one takes effect",
+            )
+        });
+        assert_eq!(
+            json(&diagnostics),
+            json!([
+                {
+                    "rule": "invalid-id",
+                    "severity": "error",
+                    "path": "body[0].id",
+                    "message": "id `a,b` can only contain numbers, letters, -, _",
+                    "location": { "file": ".github/ISSUE_TEMPLATE/bug.yml", "line": 5, "column": 9 },
+                    "related": null,
+                },
+                {
+                    "rule": "duplicate-key",
+                    "severity": "error",
+                    "path": "attributes.label",
+                    "message": "key `label` is already defined at line 3, and only this one takes effect",
+                    "location": null,
+                    "related": {
+                        "file": ".github/ISSUE_TEMPLATE/bug.yml",
+                        "line": 3,
+                        "column": 3,
+                        "message": "first defined here",
+                    },
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn sarif_results() {
+        let log = sarif(&diagnostics());
+        assert_eq!(
+            log["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "invalid-id"
+        );
+        assert_eq!(
+            log["runs"][0]["results"][0],
+            json!({
+                "ruleId": "invalid-id",
+                "ruleIndex": 0,
+                "level": "error",
+                "message": { "text": "id `a,b` can only contain numbers, letters, -, _" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": ".github/ISSUE_TEMPLATE/bug.yml" },
+                        "region": { "startLine": 5, "startColumn": 9, "endColumn": 12 },
+                    },
+                }],
+            })
+        );
+    }
+}
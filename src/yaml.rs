@@ -0,0 +1,1224 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use yaml_rust::{
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::{Marker, ScanError, TScalarStyle},
+};
+
+use crate::diagnostic::{Diagnostic, Location, Related, Rule};
+
+/// Template file kept in memory so that diagnostics can quote it.
+pub struct Document {
+    file: String,
+    source: String,
+    /// Tree with merge keys applied and the last of duplicate keys kept.
+    root: Option<Node>,
+    /// Whether serde_yaml would read the source differently from `root`.
+    resolved: bool,
+    /// Problems with the YAML itself rather than with what it describes.
+    syntax: Vec<Diagnostic>,
+}
+
+impl Document {
+    pub fn open(file: impl AsRef<Path>) -> Result<Self> {
+        let file = file.as_ref().display().to_string();
+        let source =
+            fs::read_to_string(&file).with_context(|| format!("Failed to open {}", file))?;
+        Ok(Self::new(file, source))
+    }
+
+    pub fn new(file: impl Into<String>, source: impl Into<String>) -> Self {
+        let source = source.into();
+        // Syntax errors are reported by serde_yaml in `load`,
+        // so an unparsable tree just leaves nothing to locate.
+        let tree = Node::parse(&source).ok().flatten();
+        let mut document = Self {
+            file: file.into(),
+            source,
+            root: None,
+            resolved: false,
+            syntax: vec![],
+        };
+        if let Some(tree) = tree {
+            let mut syntax = vec![];
+            document.check_syntax(&tree, "", &mut syntax);
+            document.syntax = syntax;
+            document.resolved = tree.needs_resolution();
+            document.root = Some(tree.resolve());
+        }
+        document
+    }
+
+    /// Deserializes the document and runs `validate` over the result.
+    /// Every diagnostic returned is located in the source.
+    pub fn load<T: DeserializeOwned>(
+        &self,
+        validate: impl Fn(&T) -> Vec<Diagnostic>,
+    ) -> (Option<T>, Vec<Diagnostic>) {
+        let mut diagnostics = self.syntax.clone();
+        // serde_yaml 0.8 neither applies merge keys nor accepts duplicate fields,
+        // so such documents are deserialized from the resolved tree written back as YAML instead.
+        let resolved = self.root.as_ref().filter(|_| self.resolved);
+        let value = match resolved {
+            Some(root) => serde_yaml::from_str::<T>(&root.to_yaml()),
+            None => serde_yaml::from_str::<T>(&self.source),
+        };
+        match value {
+            Ok(value) => {
+                diagnostics.extend(
+                    validate(&value)
+                        .into_iter()
+                        .map(|diagnostic| self.locate(diagnostic)),
+                );
+                (Some(value), diagnostics)
+            }
+            Err(err) => {
+                diagnostics.push(self.deserialize_error::<T>(err, resolved.is_none()));
+                (None, diagnostics)
+            }
+        }
+    }
+
+    /// Reports duplicate keys, and aliases and merge keys that GitHub may reject.
+    fn check_syntax(&self, node: &Node, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+        if node.alias {
+            diagnostics.push(Diagnostic {
+                location: Some(self.location(node.line, node.column, node.width)),
+                ..Diagnostic::new(
+                    Rule::UnsupportedSyntax,
+                    path,
+                    "GitHub may not accept aliases, consider repeating the anchored value",
+                )
+            });
+            // The content is checked where it is anchored.
+            return;
+        }
+        match &node.value {
+            Value::Sequence(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check_syntax(item, &format!("{path}[{index}]"), diagnostics);
+                }
+            }
+            Value::Mapping(entries) => {
+                let mut keys: Vec<&Node> = vec![];
+                for (key, value) in entries {
+                    if key.is_merge_key() {
+                        diagnostics.push(Diagnostic {
+                            location: Some(self.location(key.line, key.column, key.width)),
+                            ..Diagnostic::new(
+                                Rule::UnsupportedSyntax,
+                                path,
+                                "GitHub may not accept merge keys, which YAML 1.2 dropped",
+                            )
+                        });
+                        continue;
+                    }
+                    let name = match key.as_str() {
+                        Some(name) => name,
+                        None => continue,
+                    };
+                    let path = child_path(path, name);
+                    match keys.iter().find(|first| first.as_str() == Some(name)) {
+                        Some(first) => diagnostics.push(Diagnostic {
+                            location: Some(self.location(key.line, key.column, key.width)),
+                            related: Some(Related {
+                                message: "first defined here".to_string(),
+                                location: self.location(first.line, first.column, first.width),
+                            }),
+                            ..Diagnostic::new(
+                                Rule::DuplicateKey,
+                                path.clone(),
+                                format!(
+                                    "key `{name}` is already defined at line {}, and only this one takes effect",
+                                    first.line
+                                ),
+                            )
+                        }),
+                        None => keys.push(key),
+                    }
+                    self.check_syntax(value, &path, diagnostics);
+                }
+            }
+            Value::Scalar(..) => {}
+        }
+    }
+
+    /// Reports what serde accepts without complaint but GitHub reads differently:
+    /// plain scalars in string positions of `schema` that YAML 1.1 resolves to other types,
+    /// and, if `strict` is set, keys that `schema` does not know of.
+    pub fn lint(&self, schema: &Schema, strict: bool) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        if let Some(root) = &self.root {
+            self.walk(root, schema, "", strict, &mut diagnostics);
+        }
+        diagnostics
+    }
+
+    fn walk(
+        &self,
+        node: &Node,
+        schema: &Schema,
+        path: &str,
+        strict: bool,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        match (schema, &node.value) {
+            // A key without a value is left to deserialization, which knows whether it may be null.
+            (Schema::Text, Value::Scalar(_, TScalarStyle::Plain)) if self.is_empty(node) => {}
+            (Schema::Text, Value::Scalar(text, TScalarStyle::Plain)) => {
+                if let Some(resolved) = implicit_type(text) {
+                    diagnostics.push(Diagnostic {
+                        location: Some(self.location(node.line, node.column, node.width)),
+                        ..Diagnostic::new(
+                            Rule::ImplicitType,
+                            path,
+                            format!(
+                                "`{text}` is read as {resolved} in YAML 1.1, quote it as `{text:?}` to keep it a string"
+                            ),
+                        )
+                    });
+                }
+            }
+            (Schema::Sequence(item), Value::Sequence(items)) => {
+                for (index, node) in items.iter().enumerate() {
+                    self.walk(node, item, &format!("{path}[{index}]"), strict, diagnostics);
+                }
+            }
+            (Schema::Tagged(tag, variants), Value::Mapping(_)) => {
+                // An unknown tag fails deserialization, so there is nothing more to report.
+                let variant = node.get(tag).and_then(Node::as_str).and_then(|name| {
+                    variants
+                        .iter()
+                        .find(|(variant, _)| *variant == name)
+                        .map(|(_, schema)| schema)
+                });
+                if let Some(schema) = variant {
+                    self.walk(node, schema, path, strict, diagnostics);
+                }
+            }
+            (Schema::Mapping(keys), Value::Mapping(entries)) => {
+                for (key, value) in entries {
+                    let name = match key.as_str() {
+                        Some(name) => name,
+                        None => continue,
+                    };
+                    let path = child_path(path, name);
+                    match keys.iter().find(|(known, _)| *known == name) {
+                        Some((_, schema)) => self.walk(value, schema, &path, strict, diagnostics),
+                        None if strict => {
+                            let mut message = format!("unknown key `{name}`");
+                            if let Some(suggestion) = suggest(name, keys.iter().map(|(k, _)| *k)) {
+                                message.push_str(&format!(", did you mean `{suggestion}`?"));
+                            }
+                            diagnostics.push(Diagnostic {
+                                location: Some(self.location(key.line, key.column, key.width)),
+                                ..Diagnostic::new(Rule::UnknownKey, path, message)
+                            });
+                        }
+                        None => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether `node` is the null yaml-rust makes up for a missing value,
+    /// which it spells `~` and places at whatever follows.
+    fn is_empty(&self, node: &Node) -> bool {
+        node.as_str() == Some("~")
+            && self
+                .source
+                .lines()
+                .nth(node.line.saturating_sub(1))
+                .and_then(|line| line.chars().nth(node.column - 1))
+                != Some('~')
+    }
+
+    fn locate(&self, mut diagnostic: Diagnostic) -> Diagnostic {
+        let (line, column, width) = match &self.root {
+            Some(root) => {
+                let node = root.lookup(&diagnostic.path);
+                (node.line, node.column, node.width)
+            }
+            None => (1, 1, 1),
+        };
+        diagnostic.location = Some(self.location(line, column, width));
+        diagnostic
+    }
+
+    fn location(&self, line: usize, column: usize, width: usize) -> Location {
+        Location {
+            file: self.file.clone(),
+            line,
+            column,
+            width,
+            snippet: self
+                .source
+                .lines()
+                .nth(line.saturating_sub(1))
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+
+    /// Turns a serde_yaml error into a diagnostic, located where serde_yaml says if it read
+    /// the source itself, or else by the path to the failing node.
+    fn deserialize_error<T: DeserializeOwned>(
+        &self,
+        err: serde_yaml::Error,
+        in_source: bool,
+    ) -> Diagnostic {
+        let location = err
+            .location()
+            .filter(|_| in_source)
+            .map(|location| self.location(location.line(), location.column(), 1));
+        let (path, message) = split_error(&err);
+        if let Some((path, message)) = self.narrow::<T>(&path) {
+            return self.locate(Diagnostic::new(Rule::Deserialize, path, message));
+        }
+        let diagnostic = Diagnostic::new(Rule::Deserialize, path, message);
+        match location {
+            Some(location) => Diagnostic {
+                location: Some(location),
+                ..diagnostic
+            },
+            None => self.locate(diagnostic),
+        }
+    }
+
+    /// Finds which item of the sequence at `path` serde_yaml failed at, and where in it.
+    /// Internally tagged enums, such as form elements, are buffered before they are deserialized,
+    /// so serde_yaml only points at the sequence. Each item is tried alone in its place instead,
+    /// and then the node the error names is looked for in the failing one.
+    fn narrow<T: DeserializeOwned>(&self, path: &str) -> Option<(String, String)> {
+        let root = self.root.as_ref()?;
+        let items = match &root.lookup(path).value {
+            Value::Sequence(items) if !path.is_empty() => items,
+            _ => return None,
+        };
+        let failure = |tree: &Node| {
+            serde_yaml::from_str::<T>(&tree.to_yaml())
+                .err()
+                .map(|err| split_error(&err).1)
+        };
+        // An error about the sequence itself stays there.
+        let mut empty = root.clone();
+        empty.lookup_mut(path).value = Value::Sequence(vec![]);
+        if failure(&empty).is_some() {
+            return None;
+        }
+        let (index, trial, message) = items.iter().enumerate().find_map(|(index, item)| {
+            let mut trial = root.clone();
+            trial.lookup_mut(path).value = Value::Sequence(vec![item.clone()]);
+            let message = failure(&trial)?;
+            Some((index, trial, message))
+        })?;
+        let item = &items[index];
+        let mut nodes = vec![];
+        item.descendants("", &mut nodes);
+        let field = match message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split_once('`'))
+        {
+            // The mapping missing the field is the one where adding it changes the error.
+            Some((key, _)) => nodes
+                .iter()
+                .filter(|(_, node)| {
+                    matches!(node.value, Value::Mapping(_)) && node.get(key).is_none()
+                })
+                .find(|(relative, _)| {
+                    let mut attempt = trial.clone();
+                    let mapping = attempt.lookup_mut(&join_path(&format!("{path}[0]"), relative));
+                    if let Value::Mapping(entries) = &mut mapping.value {
+                        entries.push((Node::scalar(key), Node::scalar("~")));
+                    }
+                    failure(&attempt).as_ref() != Some(&message)
+                })
+                .map(|(relative, _)| join_path(relative, key)),
+            // Otherwise the error quotes the offending value before saying what was expected.
+            None => {
+                let subject = message.split(", expected").next().unwrap_or_default();
+                nodes
+                    .iter()
+                    .find(|(_, node)| {
+                        node.as_str().is_some_and(|text| {
+                            subject.contains(&format!("{text:?}"))
+                                || subject.contains(&format!("`{text}`"))
+                        })
+                    })
+                    .map(|(relative, _)| relative.clone())
+            }
+        };
+        let element = format!("{path}[{index}]");
+        Some((join_path(&element, &field.unwrap_or_default()), message))
+    }
+}
+
+/// Splits a serde_yaml error into the path to the failing node and the message without position.
+fn split_error(err: &serde_yaml::Error) -> (String, String) {
+    let mut message = err.to_string();
+    if let Some(location) = err.location() {
+        let suffix = format!(" at line {} column {}", location.line(), location.column());
+        if let Some(stripped) = message.strip_suffix(&suffix) {
+            message = stripped.to_string();
+        }
+    }
+    // serde_yaml prefixes messages with the path to the failing node unless it is the root.
+    match message.split_once(": ") {
+        Some((path, rest)) if !path.contains(char::is_whitespace) && !path.contains('`') => {
+            (path.to_string(), rest.to_string())
+        }
+        _ => (String::new(), message),
+    }
+}
+
+/// Appends `relative`, a path such as `attributes.options[0]` or `[1]`, to `path`.
+fn join_path(path: &str, relative: &str) -> String {
+    if relative.is_empty() || relative.starts_with('[') {
+        format!("{path}{relative}")
+    } else {
+        child_path(path, relative)
+    }
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    match path {
+        "" => key.to_string(),
+        _ => format!("{path}.{key}"),
+    }
+}
+
+/// Keys a document may have, for finding the ones serde ignores.
+pub enum Schema {
+    /// Any value, whose keys are not checked if it is a mapping.
+    Any,
+    /// String, which plain scalars of other types may sneak into.
+    Text,
+    Sequence(&'static Schema),
+    Mapping(&'static [(&'static str, Schema)]),
+    /// Mapping whose keys depend on the value of the tag key, as in internally tagged enums.
+    Tagged(&'static str, &'static [(&'static str, Schema)]),
+}
+
+/// Picks the candidate closest to a mistyped `key`, if any is close enough.
+pub fn suggest<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    candidates
+        .map(|candidate| (strsim::jaro_winkler(key, candidate), candidate))
+        .filter(|(similarity, _)| *similarity > 0.8)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, candidate)| candidate)
+}
+
+/// Describes what YAML 1.1, which GitHub follows, resolves a plain scalar to unless it is a string.
+/// Booleans are the ones GitHub's parser reads as such, in any case and without `y` and `n`.
+// cf. https://yaml.org/type/
+// cf. https://github.com/ruby/psych/blob/master/lib/psych/scalar_scanner.rb
+fn implicit_type(text: &str) -> Option<String> {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Some("null".to_string()),
+        _ if ["yes", "true", "on"]
+            .iter()
+            .any(|word| text.eq_ignore_ascii_case(word)) =>
+        {
+            return Some("the boolean true".to_string())
+        }
+        _ if ["no", "false", "off"]
+            .iter()
+            .any(|word| text.eq_ignore_ascii_case(word)) =>
+        {
+            return Some("the boolean false".to_string())
+        }
+        _ => {}
+    }
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let lower = unsigned.to_ascii_lowercase();
+    if lower == ".inf" || (lower == ".nan" && unsigned == text) {
+        return Some("a number".to_string());
+    }
+    let digits = |s: &str, radix: u32| {
+        !s.is_empty()
+            && s.chars().any(|c| c != '_')
+            && s.chars().all(|c| c == '_' || c.is_digit(radix))
+    };
+    let integer = if let Some(binary) = unsigned.strip_prefix("0b") {
+        digits(binary, 2)
+    } else if let Some(hex) = unsigned.strip_prefix("0x") {
+        digits(hex, 16)
+    } else {
+        unsigned == "0"
+            || (!unsigned.starts_with('0') && digits(unsigned, 10))
+            || is_sexagesimal(unsigned)
+            || (unsigned.starts_with('0') && digits(unsigned, 8))
+    };
+    if integer {
+        return Some(describe_number(text, integer_value(text)));
+    }
+    let float = match unsigned.split_once('.') {
+        Some((whole, fraction)) => {
+            let (fraction, exponent) = match fraction.split_once(['e', 'E']) {
+                Some((fraction, exponent)) => (fraction, Some(exponent)),
+                None => (fraction, None),
+            };
+            (whole.is_empty() || digits(whole, 10) || is_sexagesimal(whole))
+                && (fraction.is_empty() || digits(fraction, 10))
+                && (whole
+                    .chars()
+                    .chain(fraction.chars())
+                    .any(|c| c.is_ascii_digit()))
+                && exponent.is_none_or(|exponent| {
+                    exponent
+                        .strip_prefix(['-', '+'])
+                        .is_some_and(|exponent| digits(exponent, 10) && !exponent.contains('_'))
+                })
+        }
+        None => false,
+    };
+    if float {
+        let value = text.replace('_', "").parse::<f64>().ok();
+        return Some(describe_number(
+            text,
+            value.map(|value| format!("{value:?}")),
+        ));
+    }
+    if is_timestamp(text) {
+        return Some("a timestamp".to_string());
+    }
+    None
+}
+
+/// Whether `text` is a base 60 number such as `1:30`, which YAML 1.1 reads as 90.
+fn is_sexagesimal(text: &str) -> bool {
+    let mut parts = text.split(':');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = parts.peekable();
+    rest.peek().is_some()
+        && first.starts_with(|c: char| c.is_ascii_digit() && c != '0')
+        && first.chars().all(|c| c == '_' || c.is_ascii_digit())
+        && rest.all(|part| {
+            matches!(part.len(), 1 | 2)
+                && part.chars().all(|c| c.is_ascii_digit())
+                && part.parse::<u8>().is_ok_and(|n| n < 60)
+        })
+}
+
+fn integer_value(text: &str) -> Option<String> {
+    let text = text.replace('_', "");
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text.strip_prefix('+').unwrap_or(&text)),
+    };
+    let value = if let Some(binary) = unsigned.strip_prefix("0b") {
+        i64::from_str_radix(binary, 2).ok()?
+    } else if let Some(hex) = unsigned.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if unsigned.contains(':') {
+        // A sum too large for i64 leaves the value undescribed rather than wrapping around.
+        unsigned.split(':').try_fold(0i64, |sum, part| {
+            sum.checked_mul(60)?.checked_add(part.parse().ok()?)
+        })?
+    } else if unsigned.len() > 1 && unsigned.starts_with('0') {
+        i64::from_str_radix(unsigned, 8).ok()?
+    } else {
+        unsigned.parse().ok()?
+    };
+    Some(if negative { -value } else { value }.to_string())
+}
+
+fn describe_number(text: &str, value: Option<String>) -> String {
+    match value {
+        Some(value) if value != text => format!("the number {value}"),
+        _ => "a number".to_string(),
+    }
+}
+
+/// Whether `text` starts with a date such as `2022-07-01`, optionally followed by a time.
+fn is_timestamp(text: &str) -> bool {
+    let (date, time) = match text.find(['T', 't', ' ', '\t']) {
+        Some(index) => (&text[..index], Some(text[index + 1..].trim_start())),
+        None => (text, None),
+    };
+    let parts = date.split('-').collect::<Vec<_>>();
+    let is_date = parts.len() == 3
+        && parts[0].len() == 4
+        && matches!(parts[1].len(), 1 | 2)
+        && matches!(parts[2].len(), 1 | 2)
+        && parts
+            .iter()
+            .all(|part| part.chars().all(|c| c.is_ascii_digit()));
+    let is_time = time.is_none_or(|time| {
+        let mut parts = time.splitn(3, ':');
+        let hour = parts.next().unwrap_or_default();
+        let minute = parts.next().unwrap_or_default();
+        let second = parts.next().unwrap_or_default();
+        matches!(hour.len(), 1 | 2)
+            && hour.chars().all(|c| c.is_ascii_digit())
+            && minute.len() == 2
+            && minute.chars().all(|c| c.is_ascii_digit())
+            && second
+                .get(..2)
+                .is_some_and(|second| second.chars().all(|c| c.is_ascii_digit()))
+    });
+    is_date && is_time
+}
+
+/// YAML node that remembers where it was written in the source.
+/// serde_yaml throws positions away once deserialization succeeds,
+/// so this tree is built alongside to point diagnostics at the right place.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub value: Value,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number.
+    pub column: usize,
+    /// Number of characters to highlight in a code frame.
+    pub width: usize,
+    /// Whether the node is a copy of an anchored one.
+    pub alias: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Scalar(String, TScalarStyle),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(Node, Node)>),
+}
+
+impl Node {
+    /// Parses the first document in `source`, which is `None` if the source is empty.
+    pub fn parse(source: &str) -> Result<Option<Node>, ScanError> {
+        let mut builder = Builder::default();
+        Parser::new(source.chars()).load(&mut builder, false)?;
+        Ok(builder.root)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        match &self.value {
+            Value::Mapping(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_str() == Some(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn index(&self, index: usize) -> Option<&Node> {
+        match &self.value {
+            Value::Sequence(items) => items.get(index),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::Scalar(scalar, _) => Some(scalar),
+            _ => None,
+        }
+    }
+
+    fn is_merge_key(&self) -> bool {
+        matches!(&self.value, Value::Scalar(key, TScalarStyle::Plain) if key == "<<")
+    }
+
+    fn is_same_key(&self, other: &Node) -> bool {
+        matches!((self.as_str(), other.as_str()), (Some(a), Some(b)) if a == b)
+    }
+
+    /// Whether the tree has merge keys or duplicate keys, which `resolve` takes care of.
+    fn needs_resolution(&self) -> bool {
+        match &self.value {
+            Value::Scalar(..) => false,
+            Value::Sequence(items) => items.iter().any(Node::needs_resolution),
+            Value::Mapping(entries) => entries.iter().enumerate().any(|(index, (key, value))| {
+                key.is_merge_key()
+                    || entries[..index]
+                        .iter()
+                        .any(|(first, _)| first.is_same_key(key))
+                    || value.needs_resolution()
+            }),
+        }
+    }
+
+    /// Applies merge keys and keeps only the last of duplicate keys, as YAML 1.1 loaders do.
+    /// Keys written in a mapping take precedence over merged ones,
+    /// and earlier mappings merged take precedence over later ones.
+    // cf. https://yaml.org/type/merge.html
+    fn resolve(&self) -> Node {
+        let value = match &self.value {
+            Value::Scalar(..) => self.value.clone(),
+            Value::Sequence(items) => Value::Sequence(items.iter().map(Node::resolve).collect()),
+            Value::Mapping(entries) => {
+                let mut resolved: Vec<(Node, Node)> = vec![];
+                let mut sources = vec![];
+                for (key, value) in entries {
+                    if key.is_merge_key() {
+                        match &value.value {
+                            Value::Sequence(items) => sources.extend(items),
+                            _ => sources.push(value),
+                        }
+                        continue;
+                    }
+                    let entry = (key.clone(), value.resolve());
+                    match resolved
+                        .iter_mut()
+                        .find(|(first, _)| first.is_same_key(key))
+                    {
+                        Some(first) => *first = entry,
+                        None => resolved.push(entry),
+                    }
+                }
+                for source in sources {
+                    if let Value::Mapping(entries) = source.resolve().value {
+                        for (key, value) in entries {
+                            if !resolved.iter().any(|(first, _)| first.is_same_key(&key)) {
+                                resolved.push((key, value));
+                            }
+                        }
+                    }
+                }
+                Value::Mapping(resolved)
+            }
+        };
+        Node {
+            value,
+            line: self.line,
+            column: self.column,
+            width: self.width,
+            alias: self.alias,
+        }
+    }
+
+    /// Writes the tree back as block YAML, keeping plain scalars plain and quoting the others,
+    /// so that serde_yaml reads it the same way as the source.
+    fn to_yaml(&self) -> String {
+        let mut yaml = String::new();
+        self.write_yaml(0, &mut yaml);
+        yaml
+    }
+
+    /// Appends the node to `yaml`, which ends right after `key:` or `-`, or is empty for the root.
+    fn write_yaml(&self, indent: usize, yaml: &mut String) {
+        match &self.value {
+            Value::Scalar(..) => {
+                yaml.push(' ');
+                yaml.push_str(&self.scalar_yaml());
+                yaml.push('\n');
+            }
+            Value::Sequence(items) if items.is_empty() => yaml.push_str(" []\n"),
+            Value::Mapping(entries) if entries.is_empty() => yaml.push_str(" {}\n"),
+            Value::Sequence(items) => {
+                yaml.push('\n');
+                for item in items {
+                    yaml.push_str(&" ".repeat(indent));
+                    yaml.push('-');
+                    item.write_yaml(indent + 2, yaml);
+                }
+            }
+            Value::Mapping(entries) => {
+                yaml.push('\n');
+                for (key, value) in entries {
+                    yaml.push_str(&" ".repeat(indent));
+                    yaml.push_str(&key.scalar_yaml());
+                    yaml.push(':');
+                    value.write_yaml(indent + 2, yaml);
+                }
+            }
+        }
+    }
+
+    fn scalar_yaml(&self) -> String {
+        match &self.value {
+            Value::Scalar(text, TScalarStyle::Plain) => text.clone(),
+            // JSON strings are valid double-quoted YAML scalars.
+            _ => serde_json::to_string(self.as_str().unwrap_or_default())
+                .expect("Strings always serialize to JSON."),
+        }
+    }
+
+    /// Follows a path such as `body[2].attributes.options[0]` as far as possible
+    /// and returns the deepest node reached.
+    pub fn lookup(&self, path: &str) -> &Node {
+        let mut node = self;
+        for segment in segments(path) {
+            match node.child(&segment) {
+                Some(next) => node = next,
+                None => return node,
+            }
+        }
+        node
+    }
+
+    /// Same as `lookup`, for changing the node reached.
+    fn lookup_mut(&mut self, path: &str) -> &mut Node {
+        let mut node = self;
+        for segment in segments(path) {
+            if node.child(&segment).is_none() {
+                return node;
+            }
+            node = match (&mut node.value, segment) {
+                (Value::Mapping(entries), Segment::Key(key)) => entries
+                    .iter_mut()
+                    .find(|(k, _)| k.as_str() == Some(key))
+                    .map(|(_, v)| v),
+                (Value::Sequence(items), Segment::Index(index)) => items.get_mut(index),
+                _ => None,
+            }
+            .expect("The child was just found.");
+        }
+        node
+    }
+
+    fn child(&self, segment: &Segment) -> Option<&Node> {
+        match segment {
+            Segment::Key(key) => self.get(key),
+            Segment::Index(index) => self.index(*index),
+        }
+    }
+
+    /// Collects the node and everything under it, with their paths relative to `path`.
+    fn descendants<'a>(&'a self, path: &str, nodes: &mut Vec<(String, &'a Node)>) {
+        nodes.push((path.to_string(), self));
+        match &self.value {
+            Value::Scalar(..) => {}
+            Value::Sequence(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    item.descendants(&format!("{path}[{index}]"), nodes);
+                }
+            }
+            Value::Mapping(entries) => {
+                for (key, value) in entries {
+                    if let Some(key) = key.as_str() {
+                        value.descendants(&child_path(path, key), nodes);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Plain scalar that is not written anywhere in the source.
+    fn scalar(text: &str) -> Node {
+        Node {
+            value: Value::Scalar(text.to_string(), TScalarStyle::Plain),
+            line: 1,
+            column: 1,
+            width: 1,
+            alias: false,
+        }
+    }
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Splits a path such as `body[2].attributes` into keys and indices, up to the first invalid index.
+fn segments(path: &str) -> impl Iterator<Item = Segment<'_>> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .flat_map(|segment| {
+            let (key, indices) = segment.split_once('[').unwrap_or((segment, ""));
+            let key = (!key.is_empty()).then_some(Some(Segment::Key(key)));
+            let indices = indices
+                .split('[')
+                .map(|index| index.trim_end_matches(']'))
+                .filter(|index| !index.is_empty())
+                .map(|index| index.parse().ok().map(Segment::Index));
+            key.into_iter().chain(indices)
+        })
+        .map_while(|segment| segment)
+}
+
+enum Frame {
+    Sequence(Node, usize),
+    Mapping(Node, usize, Option<Node>),
+}
+
+#[derive(Default)]
+struct Builder {
+    stack: Vec<Frame>,
+    anchors: HashMap<usize, Node>,
+    root: Option<Node>,
+}
+
+impl Builder {
+    fn push(&mut self, node: Node, anchor: usize) {
+        if anchor > 0 {
+            self.anchors.insert(anchor, node.clone());
+        }
+        match self.stack.last_mut() {
+            None => self.root = Some(node),
+            Some(Frame::Sequence(parent, _)) => {
+                if let Value::Sequence(items) = &mut parent.value {
+                    items.push(node);
+                }
+            }
+            Some(Frame::Mapping(parent, _, key)) => match key.take() {
+                None => {
+                    // yaml-rust marks block mappings at the first colon,
+                    // so the first key is a better place to point at.
+                    if matches!(&parent.value, Value::Mapping(entries) if entries.is_empty()) {
+                        parent.line = node.line;
+                        parent.column = node.column;
+                    }
+                    *key = Some(node)
+                }
+                Some(k) => {
+                    if let Value::Mapping(entries) = &mut parent.value {
+                        entries.push((k, node));
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl MarkedEventReceiver for Builder {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        let node = |value, width| Node {
+            value,
+            line: marker.line(),
+            column: marker.col() + 1,
+            width,
+            alias: false,
+        };
+        match event {
+            Event::Scalar(scalar, style, anchor, _) => {
+                let width = match style {
+                    _ if scalar.contains('\n') => 1,
+                    TScalarStyle::Plain => scalar.chars().count().max(1),
+                    TScalarStyle::SingleQuoted | TScalarStyle::DoubleQuoted => {
+                        scalar.chars().count() + 2
+                    }
+                    _ => 1,
+                };
+                self.push(node(Value::Scalar(scalar, style), width), anchor);
+            }
+            Event::Alias(anchor) => {
+                if let Some(target) = self.anchors.get(&anchor) {
+                    let alias = Node {
+                        line: marker.line(),
+                        column: marker.col() + 1,
+                        width: 1,
+                        alias: true,
+                        ..target.clone()
+                    };
+                    self.push(alias, 0);
+                }
+            }
+            Event::SequenceStart(anchor) => self
+                .stack
+                .push(Frame::Sequence(node(Value::Sequence(vec![]), 1), anchor)),
+            Event::MappingStart(anchor) => self.stack.push(Frame::Mapping(
+                node(Value::Mapping(vec![]), 1),
+                anchor,
+                None,
+            )),
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some(Frame::Sequence(node, anchor) | Frame::Mapping(node, anchor, _)) =
+                    self.stack.pop()
+                {
+                    self.push(node, anchor);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn deserialize_error() {
+        let document = Document::new("bug.yml", "name: Bug\nbody:\n  - type: input\n    id: 1\n");
+        let (value, diagnostics) = document.load::<HashMap<String, Vec<String>>>(|_| vec![]);
+        assert!(value.is_none());
+        assert_eq!(
+            diagnostics[0].to_string(),
+            r#"error[deserialize]: name: invalid type: string "Bug", expected a sequence
+ --> bug.yml:1:7
+  |
+1 | name: Bug
+  |       ^"#
+        );
+    }
+
+    #[test]
+    fn tagged_sequence_errors() {
+        #[derive(Debug, serde::Deserialize)]
+        #[serde(tag = "type", rename_all = "lowercase")]
+        #[allow(dead_code)]
+        enum Element {
+            Input { attributes: Attributes },
+        }
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Attributes {
+            label: String,
+            required: Option<bool>,
+        }
+        let error = |source: &str| {
+            let document = Document::new("bug.yml", source);
+            let (value, diagnostics) = document.load::<HashMap<String, Vec<Element>>>(|_| vec![]);
+            assert!(value.is_none());
+            let diagnostic = diagnostics.last().unwrap();
+            (
+                diagnostic.path.clone(),
+                diagnostic.message.clone(),
+                diagnostic.location.as_ref().unwrap().line,
+            )
+        };
+        let source = "body:\n  - type: input\n    attributes:\n      label: A\n  - type: input\n    attributes:\n      label: B\n      required: maybe\n";
+        assert_eq!(
+            error(source),
+            (
+                "body[1].attributes.required".to_string(),
+                r#"invalid type: string "maybe", expected a boolean"#.to_string(),
+                8
+            )
+        );
+        let source = "body:\n  - type: input\n    attributes:\n      label: A\n  - type: input\n    attributes:\n      required: true\n";
+        assert_eq!(
+            error(source),
+            (
+                "body[1].attributes.label".to_string(),
+                "missing field `label`".to_string(),
+                7
+            )
+        );
+    }
+
+    #[test]
+    fn lookup() {
+        let source = r#"name: Bug
+body:
+  - type: input
+    id: "version"
+    attributes:
+      options: [a, b]
+"#;
+        let root = Node::parse(source).unwrap().unwrap();
+        let position = |path| {
+            let node = root.lookup(path);
+            (node.line, node.column, node.width)
+        };
+        assert_eq!(position("name"), (1, 7, 3));
+        assert_eq!(position("body[0].id"), (4, 9, 9));
+        assert_eq!(position("body[0].attributes.options[1]"), (6, 20, 1));
+        assert_eq!(position("body[0].attributes.label"), (6, 7, 1));
+        assert_eq!(position("body[3]"), (3, 3, 1));
+    }
+
+    #[test]
+    fn unknown_keys() {
+        const SCHEMA: Schema = Schema::Mapping(&[
+            ("name", Schema::Any),
+            (
+                "body",
+                Schema::Sequence(&Schema::Tagged(
+                    "type",
+                    &[(
+                        "input",
+                        Schema::Mapping(&[("type", Schema::Any), ("placeholder", Schema::Any)]),
+                    )],
+                )),
+            ),
+        ]);
+        let document = Document::new(
+            "bug.yml",
+            "name: Bug\nbody:\n  - type: input\n    placeholer: x\n  - type: textarea\n    foo: y\nbdoy: []\n",
+        );
+        let diagnostics = document
+            .lint(&SCHEMA, true)
+            .into_iter()
+            .map(|diagnostic| {
+                let location = diagnostic.location.unwrap();
+                (
+                    diagnostic.path,
+                    diagnostic.message,
+                    location.line,
+                    location.column,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    "body[0].placeholer".to_string(),
+                    "unknown key `placeholer`, did you mean `placeholder`?".to_string(),
+                    4,
+                    5
+                ),
+                (
+                    "bdoy".to_string(),
+                    "unknown key `bdoy`, did you mean `body`?".to_string(),
+                    7,
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn implicit_types() {
+        const SCHEMA: Schema = Schema::Mapping(&[("options", Schema::Sequence(&Schema::Text))]);
+        let document = Document::new(
+            "bug.yml",
+            "options:\n  - 1.10\n  - no\n  - \"yes\"\n  - 010\n  - 1.2.3\n  - 2022-07-01\n  - 1:30\n  - ~\n  - y\n  - N\n  - yEs\n  - oN\n  -\n",
+        );
+        let messages = document
+            .lint(&SCHEMA, false)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.path, diagnostic.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    "options[0]".to_string(),
+                    r#"`1.10` is read as the number 1.1 in YAML 1.1, quote it as `"1.10"` to keep it a string"#.to_string()
+                ),
+                (
+                    "options[1]".to_string(),
+                    r#"`no` is read as the boolean false in YAML 1.1, quote it as `"no"` to keep it a string"#.to_string()
+                ),
+                (
+                    "options[3]".to_string(),
+                    r#"`010` is read as the number 8 in YAML 1.1, quote it as `"010"` to keep it a string"#.to_string()
+                ),
+                (
+                    "options[5]".to_string(),
+                    r#"`2022-07-01` is read as a timestamp in YAML 1.1, quote it as `"2022-07-01"` to keep it a string"#.to_string()
+                ),
+                (
+                    "options[6]".to_string(),
+                    r#"`1:30` is read as the number 90 in YAML 1.1, quote it as `"1:30"` to keep it a string"#.to_string()
+                ),
+                (
+                    "options[7]".to_string(),
+                    r#"`~` is read as null in YAML 1.1, quote it as `"~"` to keep it a string"#.to_string()
+                ),
+                (
+                    "options[10]".to_string(),
+                    r#"`yEs` is read as the boolean true in YAML 1.1, quote it as `"yEs"` to keep it a string"#.to_string()
+                ),
+                (
+                    "options[11]".to_string(),
+                    r#"`oN` is read as the boolean true in YAML 1.1, quote it as `"oN"` to keep it a string"#.to_string()
+                ),
+            ]
+        );
+
+        // A missing value is not mistaken for the `~` yaml-rust spells it as.
+        const MAPPING: Schema = Schema::Mapping(&[("label", Schema::Text), ("id", Schema::Text)]);
+        let document = Document::new("bug.yml", "label:\nid: os\n");
+        assert!(document.lint(&MAPPING, false).is_empty());
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let document = Document::new(
+            "bug.yml",
+            "name: Bug\nattributes:\n  label: OS\n  label: Version\n",
+        );
+        let (value, diagnostics) = document.load::<HashMap<String, serde_yaml::Value>>(|_| vec![]);
+        assert_eq!(
+            value.unwrap()["attributes"]["label"],
+            serde_yaml::Value::String("Version".to_string())
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            r#"error[duplicate-key]: attributes.label: key `label` is already defined at line 3, and only this one takes effect
+ --> bug.yml:4:3
+  |
+4 |   label: Version
+  |   ^^^^^
+note: first defined here
+ --> bug.yml:3:3
+  |
+3 |   label: OS
+  |   ^^^^^"#
+        );
+    }
+
+    #[test]
+    fn merge_keys() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Attributes {
+            label: String,
+            description: String,
+            required: bool,
+        }
+        let document = Document::new(
+            "bug.yml",
+            "- &base\n  label: OS\n  description: Shared\n  required: true\n- <<: *base\n  label: Version\n",
+        );
+        let (value, diagnostics) = document.load::<Vec<Attributes>>(|_| vec![]);
+        assert_eq!(
+            value.unwrap()[1],
+            Attributes {
+                label: "Version".to_string(),
+                description: "Shared".to_string(),
+                required: true,
+            }
+        );
+        let rules = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.rule.name(), diagnostic.path.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(rules, vec![("unsupported-syntax", "[1]")]);
+    }
+
+    #[test]
+    fn non_ascii_timestamps() {
+        assert!(is_timestamp("2024-01-01 12:34:56"));
+        assert!(!is_timestamp("2024-01-01 12:34:€uro"));
+        assert!(!is_timestamp("2024-01-01 12:34:5€"));
+    }
+
+    #[test]
+    fn large_sexagesimal_numbers() {
+        assert_eq!(integer_value("1:30"), Some("90".to_string()));
+        assert_eq!(integer_value("999999999999999999:00"), None);
+        assert_eq!(
+            implicit_type("999999999999999999:00"),
+            Some("a number".to_string())
+        );
+    }
+
+    #[test]
+    fn resolved_scalars_and_errors() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Form {
+            name: String,
+            labels: Vec<String>,
+        }
+        // Plain scalars stay strings as they do without duplicate keys.
+        let document = Document::new(
+            "bug.yml",
+            "name: Bug\nname: 2022\nlabels: [\"a: b\", yes]\n",
+        );
+        let (value, _) = document.load::<Form>(|_| vec![]);
+        assert_eq!(
+            value.unwrap(),
+            Form {
+                name: "2022".to_string(),
+                labels: vec!["a: b".to_string(), "yes".to_string()],
+            }
+        );
+
+        let document = Document::new("bug.yml", "name: Bug\nname: Bug\nlabels:\n  - a\n  - [b]\n");
+        let (value, diagnostics) = document.load::<Form>(|_| vec![]);
+        assert!(value.is_none());
+        let error = diagnostics.last().unwrap();
+        assert_eq!(error.path, "labels[1]");
+        assert_eq!(error.location.as_ref().unwrap().line, 5);
+    }
+}